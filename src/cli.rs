@@ -1,11 +1,14 @@
 // help texts is mistaken as html
 #![allow(rustdoc::invalid_html_tags)]
 
-use crate::util::{AnyRange, ParseAnyRangeError};
+use crate::table::expr::{Computation, Expr};
+use crate::util::{parse_char, ParseAnyRangeError, Selector};
 use clap::{Parser, ValueEnum};
 use std::convert::Infallible;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
+use std::num::NonZeroU16;
+use std::str::FromStr;
 
 /// TableDataMerge
 ///
@@ -20,37 +23,138 @@ pub struct Args {
     #[arg()]
     pub to: OutTypes,
 
+    /// Input data type
+    ///
+    /// Specifies the input data type for all input files.
+    /// If not provided, the type is determined by the file extension or, if that is missing or
+    /// unknown, by the file content.
+    #[arg(short, long)]
+    pub from: Option<InTypes>,
+
     /// Output file path
     ///
     /// Sets the output file path.
     /// If not provided, the output path will be generated from the input file stems and the output data type.
+    /// Use '-' to write to stdout.
     #[arg(short, long)]
     pub out: Option<String>,
 
     /// Amount of decimal places
     ///
     /// Sets the number of decimal places for numerical values, which will be correctly rounded.
-    #[arg(short, long)]
-    pub precision: Option<u16>,
+    /// Use multiple times to set different places for different columns, later rules override
+    /// earlier ones.
+    /// Argument format: '[<selector>:]<places>', without a selector all cells are selected.
+    /// Selectors are 'c<range>' for columns, 'r<range>' for rows and 'r<range>c<range>' for
+    /// single cells or blocks (e.g., 'c1..3', 'r2' or 'r0c4'), a bare range selects columns.
+    /// Rows are counted over all rows of the output, so row 0 is the header row if there is one.
+    #[arg(short, long, num_args(1), value_parser = parse_rule::<u16>)]
+    pub precision: Vec<(Selector, u16)>,
+
+    /// Amount of significant digits
+    ///
+    /// Rounds numerical values to the number of significant digits instead of decimal places,
+    /// overriding '--precision' for the selected columns.
+    /// Use multiple times to set different digits for different columns, later rules override
+    /// earlier ones.
+    /// Argument format: '[<selector>:]<digits>', without a selector all cells are selected.
+    /// Selectors follow the same syntax as for '--precision'.
+    #[arg(long, num_args(1), value_parser = parse_rule::<NonZeroU16>)]
+    pub significant: Vec<(Selector, NonZeroU16)>,
 
     /// Decimal separator
     ///
-    /// Sets the decimal separator for numerical values.
-    #[arg(short, long, default_value = "dot")]
-    pub decimal_sep: DecimalSeparator,
+    /// Sets the decimal separator for numerical values, either 'dot' (default) or 'comma'.
+    /// Use multiple times to set different separators for different columns, later rules
+    /// override earlier ones.
+    /// Argument format: '[<selector>:]<separator>', without a selector all cells are selected.
+    /// Selectors follow the same syntax as for '--precision'.
+    #[arg(short, long, num_args(1), value_parser = parse_enum_rule::<DecimalSeparator>)]
+    pub decimal_sep: Vec<(Selector, DecimalSeparator)>,
 
     /// Scientific notation
     ///
     /// Enables scientific notation for numerical cells (e.g., '1.234e+05').
-    #[arg(short, long = "exponent", default_value_t = false)]
+    #[arg(
+        short,
+        long = "exponent",
+        default_value_t = false,
+        group = "notation_flag"
+    )]
     pub exponent: bool,
 
+    /// Engineering notation
+    ///
+    /// Enables engineering notation for numerical cells, using exponents that are multiples of
+    /// three (e.g., '123.4e+03').
+    #[arg(long, default_value_t = false, group = "notation_flag")]
+    pub engineering: bool,
+
+    /// SI prefix notation
+    ///
+    /// Writes numerical cells with an SI prefix (e.g., '1.2 µ'), separated from the number by a
+    /// space, so that a suffix like 'V' follows the prefix directly.
+    /// Values outside of the SI prefixes are written in engineering notation.
+    #[arg(long, default_value_t = false, group = "notation_flag")]
+    pub si: bool,
+
+    /// Notation per column
+    ///
+    /// Sets the notation of numerical values for the selected columns, overriding '--exponent',
+    /// '--engineering' and '--si'.
+    /// Possible notations are 'decimal', 'scientific', 'engineering' and 'si'.
+    /// Use multiple times to set different notations for different columns, later rules
+    /// override earlier ones.
+    /// Argument format: '[<selector>:]<notation>', without a selector all cells are selected.
+    /// Selectors follow the same syntax as for '--precision'.
+    #[arg(long, num_args(1), value_parser = parse_enum_rule::<Notation>)]
+    pub notation: Vec<(Selector, Notation)>,
+
     /// Sign every number
     ///
     /// Forces a sign on every number.
     /// By default, only negative values have a sign. This setting forces positive values to have a "+" prefix.
-    #[arg(short, long, default_value_t = false)]
-    pub sign: bool,
+    /// Optionally restricted to cells, argument format: '--sign=<selector>' or
+    /// '--sign=[<selector>:]<true|false>', later rules override earlier ones.
+    /// Selectors follow the same syntax as for '--precision'.
+    #[arg(
+        short,
+        long,
+        num_args(0..=1),
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = parse_flag_rule
+    )]
+    pub sign: Vec<(Selector, bool)>,
+
+    /// Uncertainty columns
+    ///
+    /// Combines a value column with its uncertainty column into one column, removing the
+    /// uncertainty column.
+    /// Use multiple times to combine further pairs, all columns refer to the table before
+    /// combining.
+    /// Argument format: '<value_column>:<uncertainty_column>'.
+    /// The uncertainty is rounded to one or two significant digits and the value to the same
+    /// decimal place, ignoring '--precision'.
+    #[arg(long, num_args(1), value_parser = parse_uncertainty_pair)]
+    pub uncertainty: Vec<(usize, usize)>,
+
+    /// Uncertainty notation
+    ///
+    /// Specifies how values are written with their uncertainty.
+    /// 'pm' writes '1.23 ± 0.04', or '$1.23 \pm 0.04$' when using 'tex' output format.
+    /// 'paren' writes '1.23(4)'.
+    /// 'siunitx' writes '\num{1.23 +- 0.04}' when using 'tex' output format and falls back to 'pm'
+    /// otherwise.
+    #[arg(long, default_value = "pm")]
+    pub uncertainty_style: UncertaintyStyle,
+
+    /// Uncertainty digits
+    ///
+    /// Specifies the number of significant digits of uncertainties, either 1 or 2.
+    /// If not provided, 2 digits are used if the leading digit is 1, otherwise 1.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub uncertainty_digits: Option<u8>,
 
     /// Inject '\hline'
     ///
@@ -61,26 +165,74 @@ pub struct Args {
     /// Choose csv delimiter
     ///
     /// Specifies the value separator for 'csv' output format.
-    #[arg(short, long, default_value = ",")]
-    pub csv_sep: String,
+    /// It must be a single ascii character, which may also be given by name, e.g. 'tab'.
+    #[arg(short, long, default_value = ",", value_parser = parse_csv_char)]
+    pub csv_sep: u8,
+
+    /// Choose csv quote style
+    ///
+    /// Specifies which cells are quoted when using 'csv' output format.
+    /// With 'minimal' only cells containing the separator, the quote character or a line break are
+    /// quoted, 'non-numeric' additionally quotes all cells that are not numbers, including empty
+    /// cells.
+    #[arg(long, default_value = "minimal")]
+    pub csv_quote_style: CsvQuoteStyle,
+
+    /// Choose csv quote character
+    ///
+    /// Specifies the quote character for 'csv' output format.
+    /// It must be a single ascii character, which may also be given by name.
+    #[arg(long, default_value = "\"", value_parser = parse_csv_char)]
+    pub csv_quote: u8,
+
+    /// Choose csv line terminator
+    ///
+    /// Specifies the line terminator for 'csv' output format.
+    #[arg(long, default_value = "lf")]
+    pub csv_terminator: LineTerminator,
+
+    /// Json numbers
+    ///
+    /// Writes numerical cells as json numbers and blank cells as 'null' when using 'json' output
+    /// format.
+    /// By default, every cell is written as a string.
+    #[arg(long, default_value_t = false)]
+    pub json_numbers: bool,
+
+    /// Json layout
+    ///
+    /// Specifies how rows are written when using 'json' output format.
+    /// The 'records' layout uses the first row as keys and writes every other row as an object.
+    #[arg(long, default_value = "rows")]
+    pub json_layout: JsonLayout,
+
+    /// Matlab variable name
+    ///
+    /// Sets the name of the variable the table is assigned to when using 'm' output format.
+    #[arg(long, default_value = "data")]
+    pub m_var: String,
 
     /// Numerical prefixes
     ///
     /// Sets prefixes for numerical cells.
-    /// Use multiple times to set different prefixes for different ranges.
-    /// Argument format: '<range>:<fix>', where 'fix' is the string placed before the cell.
+    /// Use multiple times to set different prefixes for different cells.
+    /// Argument format: '<selector>:<fix>', where 'fix' is the string placed before the cell.
+    /// Selectors are 'c<range>' for columns, 'r<range>' for rows and 'r<range>c<range>' for
+    /// single cells or blocks (e.g., 'c1..3', 'r2' or 'r0c4'), a bare range selects columns.
     /// Ranges follow Rust syntax (e.g., '1..2', '..2', '1..', '..' or '1') and are 0-indexed.
-    /// Overlapping ranges will override previous rules.
+    /// Rows are counted over all rows of the output, so row 0 is the header row if there is one.
+    /// Overlapping selectors will override previous rules.
     /// To use spaces consider the syntax: '-P "1..2: m"'.
     #[arg(short = 'P', long, num_args(1), value_parser = parse_fix)]
-    pub prefix: Vec<(AnyRange<usize>, String)>,
+    pub prefix: Vec<(Selector, String)>,
 
     /// Numerical suffixes
     ///
     /// Sets suffixes for numerical cells.
-    /// Follows the same format and behavior as the `prefix` option.
+    /// Follows the same format and behavior as the `prefix` option, including the selectors
+    /// (e.g., '-S r0c4:V' or '-S c1..3:V').
     #[arg(short = 'S', long, num_args(1), value_parser = parse_fix)]
-    pub suffix: Vec<(AnyRange<usize>, String)>,
+    pub suffix: Vec<(Selector, String)>,
 
     /// Stack tables
     ///
@@ -88,23 +240,214 @@ pub struct Args {
     #[arg(short, long, default_value_t = false)]
     pub vertical: bool,
 
+    /// Transpose merged table
+    ///
+    /// Transposes the merged table, turning its rows into columns.
+    /// Use the 'transpose' option of the additional data to transpose single input files.
+    #[arg(short, long, default_value_t = false)]
+    pub transpose: bool,
+
+    /// Computed columns
+    ///
+    /// Appends a column computed from the other columns of the merged table for every row.
+    /// Use multiple times to compute further columns, which may refer to previous ones.
+    /// Argument format: '<name> = <expression>', the name is used as header with '--header'.
+    /// Expressions support the arithmetic operators '+', '-', '*', '/', '^' and the functions
+    /// 'sqrt', 'ln', 'exp', 'abs' and 'pow', columns are referenced as in '--where'.
+    /// Missing inputs result in blank cells.
+    /// Example: "P = c1 * c2".
+    #[arg(long, num_args(1))]
+    pub compute: Vec<Computation>,
+
+    /// Row filter
+    ///
+    /// Keeps only the rows of the merged table for which the expression is true.
+    /// Use multiple times to require all expressions to be true.
+    /// Columns are referenced by their 0-indexed position (e.g. 'c2') or, with '--header', by
+    /// their name, names with special characters are quoted with backticks.
+    /// Supports the operators '==', '!=', '<', '<=', '>', '>=', '&&', '||', '!' and parentheses,
+    /// strings are quoted with ' or " and '' matches blank cells.
+    /// Example: "c2 > 0.5 && c0 != ''".
+    #[arg(long = "where", num_args(1))]
+    pub filter: Vec<Expr>,
+
+    /// Strict expressions
+    ///
+    /// Reports rows whose expressions cannot be evaluated, e.g. when comparing a string with a
+    /// number, instead of dropping them or leaving their computed cells blank.
+    #[arg(long, default_value_t = false)]
+    pub strict: bool,
+
+    /// Group key columns
+    ///
+    /// Groups the rows of the merged table by the given 0-indexed columns, separated by ','.
+    /// Every group is collapsed into one row holding the key columns followed by the aggregates
+    /// given with '--aggregate'.
+    #[arg(long, value_delimiter = ',')]
+    pub group_by: Vec<usize>,
+
+    /// Group aggregates
+    ///
+    /// Appends an aggregate of a column to every group when grouping with '--group-by'.
+    /// Use multiple times to append further aggregates, defaults to 'count'.
+    /// Argument format: '<function>[:<column>]', where 'function' is one of 'count', 'sum', 'mean',
+    /// 'median', 'min', 'max', 'std' or 'sem' and the column is only optional for 'count'.
+    /// Only numerical cells are aggregated, 'count' counts non-blank cells or, without a column,
+    /// the rows of the group.
+    #[arg(long, num_args(1), requires = "group_by", value_parser = parse_aggregate)]
+    pub aggregate: Vec<(Aggregate, Option<usize>)>,
+
+    /// Sort keys
+    ///
+    /// Sorts the merged table by the given 0-indexed column.
+    /// Use multiple times to sort by further columns if previous columns are equal.
+    /// Argument format: '<column>[:<order>]', where 'order' is either 'asc' (default) or 'desc'.
+    /// Numbers are compared by value and strings in natural order.
+    #[arg(long, num_args(1), value_parser = parse_sort_key)]
+    pub sort_by: Vec<(usize, SortOrder)>,
+
+    /// Blank cell order
+    ///
+    /// Specifies whether blank cells are sorted first or last, regardless of the sort order.
+    #[arg(long, default_value = "last")]
+    pub blanks: BlankOrder,
+
+    /// Tables have headers
+    ///
+    /// Treats the first row of every input table as a header holding the column names.
+    /// When stacking tables, columns are aligned by their names instead of their position.
+    /// When joining tables, the headers are joined regardless of the names of their key columns.
+    /// When sorting, the header is kept at the top.
+    #[arg(long, default_value_t = false)]
+    pub header: bool,
+
+    /// Join tables on a key column
+    ///
+    /// Joins tables horizontally by matching the cells of the given 0-indexed key column instead
+    /// of concatenating them row by row.
+    /// The key column of every following table is dropped.
+    #[arg(short, long, conflicts_with = "vertical")]
+    pub join_on: Option<usize>,
+
+    /// Join type
+    ///
+    /// Specifies which rows are kept when joining tables with '--join-on'.
+    /// Rows without a match are padded with blank cells.
+    #[arg(long, default_value = "inner")]
+    pub join: JoinType,
+
     /// File paths
     ///
     /// Specifies input file paths and optional additional data.
     /// Format: '<file_path>:<additional_data>'.
-    /// For .csv files, additional data can set a custom delimiter (e.g., ';' or '|').
-    /// Example: 'example.csv:;'.
+    /// Use '-' to read from stdin, consider setting the input data type with '--from'.
+    /// Additional data consists of options separated by ':', either '<key>=<value>' or '<value>'.
+    /// For all files, 'skip=<n>', 'drop=<n>' and 'rows=<range>' slice the lines before parsing and
+    /// 'cols=<ranges>' selects columns in the given order (e.g., 'cols=0,3..5') and 'transpose'
+    /// transposes the table.
+    /// For .csv files, a value sets a custom delimiter (e.g., ';' or '|') and the options 'sep',
+    /// 'quote', 'escape', 'comment', 'trim', 'flexible' and 'dialect' (excel, excel-de, unix)
+    /// configure the csv dialect.
+    /// For .dat and .txt files, a value sets a custom separator, 'ws' splits at runs of whitespace
+    /// and 'fixed' or 'fixed=<positions>' splits at fixed positions.
+    /// For .m files, a value selects a variable by its name.
+    /// Example: 'example.csv:;', 'example.csv:dialect=excel:comment=#' or 'example.m:voltage'.
     #[arg(required = true, num_args(1..), value_parser = parse_file_path)]
     pub files: Vec<(String, Option<String>)>,
 }
 
-#[derive(ValueEnum, Debug, Copy, Clone)]
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InTypes {
+    Csv,
+    Dat,
+    Json,
+    Ndjson,
+    M,
+    Md,
+    Tex,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum OutTypes {
     Csv,
     Dat,
     Tex,
     Md,
     Json,
+    Ndjson,
+    M,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone)]
+pub enum JoinType {
+    /// Keep only rows with a match in both tables
+    Inner,
+
+    /// Keep all rows of the left table
+    Left,
+
+    /// Keep all rows of the right table
+    Right,
+
+    /// Keep all rows of both tables
+    Outer,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, Default)]
+pub enum CsvQuoteStyle {
+    #[default]
+    Minimal,
+    All,
+    NonNumeric,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, Default)]
+pub enum LineTerminator {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, Default)]
+pub enum JsonLayout {
+    #[default]
+    Rows,
+    Records,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone)]
+pub enum Aggregate {
+    Count,
+    Sum,
+    Mean,
+    Median,
+    Min,
+    Max,
+    Std,
+    Sem,
+}
+
+impl Display for Aggregate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = self
+            .to_possible_value()
+            .expect("no skipped variants")
+            .get_name()
+            .to_owned();
+        write!(f, "{name}")
+    }
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone)]
+pub enum BlankOrder {
+    First,
+    Last,
 }
 
 #[derive(ValueEnum, Debug, Copy, Clone, Default)]
@@ -114,6 +457,23 @@ pub enum DecimalSeparator {
     Comma,
 }
 
+#[derive(ValueEnum, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Notation {
+    #[default]
+    Decimal,
+    Scientific,
+    Engineering,
+    Si,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, Default)]
+pub enum UncertaintyStyle {
+    #[default]
+    Pm,
+    Paren,
+    Siunitx,
+}
+
 /// Represents an error that occurs when parsing a fix (prefix or suffix).
 #[derive(Debug)]
 enum ParseFixError {
@@ -137,17 +497,210 @@ impl From<ParseAnyRangeError> for ParseFixError {
 impl Error for ParseFixError {}
 
 /// Parses a fix (prefix or suffix) provided as a command-line argument.
-/// The input should be in the format "<range>:<fix>".
-/// The range is a typical Rust range, and the fix is a string to be placed at the specified range.
-fn parse_fix(input: &str) -> Result<(AnyRange<usize>, String), ParseFixError> {
+/// The input should be in the format "<selector>:<fix>".
+/// The selector is a typical Rust range of columns, optionally marked as rows ('r<range>') or
+/// cells ('r<range>c<range>'), and the fix is a string to be placed at the selected cells.
+fn parse_fix(input: &str) -> Result<(Selector, String), ParseFixError> {
     let mut split = input.splitn(2, ':');
 
-    let range = split.next().expect("first always exists");
-    let range: AnyRange<usize> = range.parse()?;
+    let selector = split.next().expect("first always exists");
+    let selector: Selector = selector.parse()?;
 
     let fix = split.next().unwrap_or("");
 
-    Ok((range, fix.to_string()))
+    Ok((selector, fix.to_string()))
+}
+
+/// Represents an error that occurs when parsing a formatting rule.
+#[derive(Debug)]
+enum ParseRuleError {
+    InvalidRange(ParseAnyRangeError),
+    InvalidValue(String),
+}
+
+impl Display for ParseRuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseRuleError::InvalidRange(r) => Display::fmt(r, f),
+            ParseRuleError::InvalidValue(v) => write!(f, "invalid value '{v}'"),
+        }
+    }
+}
+
+impl Error for ParseRuleError {}
+
+/// Parses a formatting rule provided as a command-line argument.
+/// The input should be in the format "[<selector>:]<value>".
+/// Without a selector, the rule applies to all cells.
+fn parse_rule<T: FromStr>(input: &str) -> Result<(Selector, T), ParseRuleError> {
+    let (range, value) = split_rule(input)?;
+    let value = value
+        .trim()
+        .parse()
+        .map_err(|_| ParseRuleError::InvalidValue(value.to_string()))?;
+    Ok((range, value))
+}
+
+/// Parses a formatting rule with a value of a value enum, like [`parse_rule`].
+fn parse_enum_rule<T: ValueEnum>(input: &str) -> Result<(Selector, T), ParseRuleError> {
+    let (range, value) = split_rule(input)?;
+    let value = T::from_str(value.trim(), true)
+        .map_err(|_| ParseRuleError::InvalidValue(value.to_string()))?;
+    Ok((range, value))
+}
+
+/// Parses a formatting rule of a flag, like [`parse_rule`].
+/// Additionally, the input may be a single selector, which enables the flag for its cells.
+fn parse_flag_rule(input: &str) -> Result<(Selector, bool), ParseRuleError> {
+    match parse_rule(input) {
+        Err(e) if !input.contains(':') => input
+            .trim()
+            .parse()
+            .map(|range| (range, true))
+            .map_err(|_| e),
+        rule => rule,
+    }
+}
+
+/// Splits a formatting rule into its selector and its value.
+fn split_rule(input: &str) -> Result<(Selector, &str), ParseRuleError> {
+    match input.rsplit_once(':') {
+        Some((range, value)) => Ok((range.parse().map_err(ParseRuleError::InvalidRange)?, value)),
+        None => Ok((Selector::ALL, input)),
+    }
+}
+
+/// Represents an error that occurs when parsing a sort key.
+#[derive(Debug)]
+enum ParseSortKeyError {
+    InvalidColumn(String),
+    InvalidOrder(String),
+}
+
+impl Display for ParseSortKeyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseSortKeyError::InvalidColumn(c) => write!(f, "invalid column '{c}'"),
+            ParseSortKeyError::InvalidOrder(o) => write!(f, "invalid order '{o}'"),
+        }
+    }
+}
+
+impl Error for ParseSortKeyError {}
+
+/// Parses a sort key provided as a command-line argument.
+/// The input should be in the format "<column>[:<order>]".
+fn parse_sort_key(input: &str) -> Result<(usize, SortOrder), ParseSortKeyError> {
+    let mut split = input.splitn(2, ':');
+
+    let column = split.next().expect("first always exists");
+    let column = column
+        .trim()
+        .parse()
+        .map_err(|_| ParseSortKeyError::InvalidColumn(column.to_string()))?;
+
+    let order = match split.next().map(str::trim) {
+        None | Some("asc") => SortOrder::Asc,
+        Some("desc") => SortOrder::Desc,
+        Some(order) => return Err(ParseSortKeyError::InvalidOrder(order.to_string())),
+    };
+
+    Ok((column, order))
+}
+
+/// Represents an error that occurs when parsing a group aggregate.
+#[derive(Debug)]
+enum ParseAggregateError {
+    InvalidFunction(String),
+    InvalidColumn(String),
+    MissingColumn(Aggregate),
+}
+
+impl Display for ParseAggregateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseAggregateError::InvalidFunction(a) => write!(f, "invalid function '{a}'"),
+            ParseAggregateError::InvalidColumn(c) => write!(f, "invalid column '{c}'"),
+            ParseAggregateError::MissingColumn(a) => write!(f, "'{a}' requires a column"),
+        }
+    }
+}
+
+impl Error for ParseAggregateError {}
+
+/// Parses a group aggregate provided as a command-line argument.
+/// The input should be in the format "<function>[:<column>]".
+fn parse_aggregate(input: &str) -> Result<(Aggregate, Option<usize>), ParseAggregateError> {
+    let mut split = input.splitn(2, ':');
+
+    let function = split.next().expect("first always exists").trim();
+    let function = Aggregate::from_str(function, true)
+        .map_err(|_| ParseAggregateError::InvalidFunction(function.to_string()))?;
+
+    let column = match split.next().map(str::trim) {
+        None => None,
+        Some(column) => Some(
+            column
+                .parse()
+                .map_err(|_| ParseAggregateError::InvalidColumn(column.to_string()))?,
+        ),
+    };
+
+    match (function, column) {
+        (Aggregate::Count, None) | (_, Some(_)) => Ok((function, column)),
+        (function, None) => Err(ParseAggregateError::MissingColumn(function)),
+    }
+}
+
+/// Represents an error that occurs when parsing a pair of value and uncertainty column.
+#[derive(Debug)]
+enum ParseUncertaintyPairError {
+    InvalidColumn(String),
+    MissingColumn,
+}
+
+impl Display for ParseUncertaintyPairError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseUncertaintyPairError::InvalidColumn(c) => write!(f, "invalid column '{c}'"),
+            ParseUncertaintyPairError::MissingColumn => write!(f, "missing uncertainty column"),
+        }
+    }
+}
+
+impl Error for ParseUncertaintyPairError {}
+
+/// Parses a pair of value and uncertainty column provided as a command-line argument.
+/// The input should be in the format "<value_column>:<uncertainty_column>".
+fn parse_uncertainty_pair(input: &str) -> Result<(usize, usize), ParseUncertaintyPairError> {
+    let (value, uncertainty) = input
+        .split_once(':')
+        .ok_or(ParseUncertaintyPairError::MissingColumn)?;
+    let parse = |column: &str| {
+        column
+            .trim()
+            .parse()
+            .map_err(|_| ParseUncertaintyPairError::InvalidColumn(column.to_string()))
+    };
+    Ok((parse(value)?, parse(uncertainty)?))
+}
+
+/// Represents an error that occurs when parsing a csv character.
+#[derive(Debug)]
+struct ParseCsvCharError(String);
+
+impl Display for ParseCsvCharError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is not a single ascii character", self.0)
+    }
+}
+
+impl Error for ParseCsvCharError {}
+
+/// Parses a csv separator or quote character provided as a command-line argument.
+/// The input should be a single ascii character or its name, e.g. "tab".
+fn parse_csv_char(input: &str) -> Result<u8, ParseCsvCharError> {
+    parse_char(input).ok_or_else(|| ParseCsvCharError(input.to_string()))
 }
 
 /// Parses the input file path, which may include additional data appended with a ":".
@@ -1,5 +1,6 @@
+use crate::logger::Logger;
+use crate::table::expr::ExprError;
 use crate::table::ParseTableError;
-use paris::Logger;
 use std::{io, process};
 
 #[derive(Debug)]
@@ -10,6 +11,8 @@ pub enum TdmError {
     DetermineFileType { file: String },
     UnknownFileType { file_type: String },
     ParseTable(ParseTableError),
+    FilterRows { row: Option<usize>, error: ExprError },
+    ComputeColumn { name: String, row: Option<usize>, error: ExprError },
 }
 
 impl TdmError {
@@ -45,6 +48,21 @@ impl TdmError {
                 format!("Unknown file type '{file_type}' for parsing")
             }
             TdmError::ParseTable(e) => format!("Could not parse table, {e}"),
+            TdmError::FilterRows { row: None, error } => format!("Could not filter rows, {error}"),
+            TdmError::FilterRows {
+                row: Some(row),
+                error,
+            } => format!("Could not filter row {row}, {error}"),
+            TdmError::ComputeColumn {
+                name,
+                row: None,
+                error,
+            } => format!("Could not compute column '{name}', {error}"),
+            TdmError::ComputeColumn {
+                name,
+                row: Some(row),
+                error,
+            } => format!("Could not compute column '{name}' in row {row}, {error}"),
         }
     }
 
@@ -56,6 +74,8 @@ impl TdmError {
             TdmError::DetermineFileType { .. } => 6,
             TdmError::UnknownFileType { .. } => 7,
             TdmError::ParseTable(_) => 8,
+            TdmError::FilterRows { .. } => 9,
+            TdmError::ComputeColumn { .. } => 10,
         }
     }
 }
@@ -0,0 +1,87 @@
+use paris::formatter::colorize_string;
+use std::fmt::Display;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// A small logger in the style of `paris::Logger` that writes everything to stderr.
+///
+/// Writing to stderr keeps stdout free for the table data when writing the output to stdout.
+/// If stderr is not a terminal, messages are printed once without colors or animation.
+pub struct Logger {
+    is_terminal: bool,
+    is_loading: Arc<AtomicBool>,
+    loading_handle: Option<JoinHandle<()>>,
+}
+
+impl Logger {
+    pub fn new() -> Self {
+        Logger {
+            is_terminal: io::stderr().is_terminal(),
+            is_loading: Arc::default(),
+            loading_handle: None,
+        }
+    }
+
+    /// Starts a loading animation with the given message.
+    pub fn loading<T: Display>(&mut self, message: T) -> &mut Self {
+        self.done();
+        if !self.is_terminal {
+            eprintln!("{message}");
+            return self;
+        }
+
+        self.is_loading.store(true, Ordering::SeqCst);
+        let is_loading = self.is_loading.clone();
+        let message = message.to_string();
+        self.loading_handle = Some(thread::spawn(move || {
+            let frames = ["⠦", "⠇", "⠋", "⠙", "⠸", "⠴"];
+            for frame in frames.iter().cycle() {
+                if !is_loading.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                eprint!(
+                    "\r{}",
+                    colorize_string(format!("<cyan>{frame}</> {message}"))
+                );
+                io::stderr().flush().ok();
+                thread::sleep(Duration::from_millis(100));
+            }
+        }));
+
+        self
+    }
+
+    /// Stops the loading animation and clears its line.
+    pub fn done(&mut self) -> &mut Self {
+        if let Some(handle) = self.loading_handle.take() {
+            self.is_loading.store(false, Ordering::SeqCst);
+            handle.join().expect("could not join loading thread");
+            eprint!("\r\x1B[2K");
+        }
+        self
+    }
+
+    /// Prints a message with a success icon.
+    pub fn success<T: Display>(&mut self, message: T) -> &mut Self {
+        self.print("<green><tick></>", message)
+    }
+
+    /// Prints a message with an error icon.
+    pub fn error<T: Display>(&mut self, message: T) -> &mut Self {
+        self.print("<red><cross></>", message)
+    }
+
+    /// Prints a message with an icon, only the message if stderr is not a terminal.
+    fn print<T: Display>(&mut self, icon: &str, message: T) -> &mut Self {
+        self.done();
+        match self.is_terminal {
+            true => eprintln!("{}", colorize_string(format!("{icon} {message}"))),
+            false => eprintln!("{message}"),
+        }
+        self
+    }
+}
@@ -1,20 +1,25 @@
 use clap::Parser;
-use paris::Logger;
 use std::ffi::OsStr;
 use std::fs;
+use std::io::{self, Read, Write};
 use std::path::Path;
 
 use crate::error::TdmError;
-use cli::{Args, OutTypes};
-use table::Table;
+use crate::logger::Logger;
+use cli::{Aggregate, Args, InTypes, OutTypes};
+use table::{detect_type, InputOptions, Table};
 
 use crate::table::FormatOptions;
 
 mod cli;
 mod error;
+mod logger;
 pub mod table;
 pub mod util;
 
+/// The path used for reading from stdin or writing to stdout.
+const STDIO: &str = "-";
+
 fn main() {
     let args = Args::parse();
 
@@ -25,7 +30,14 @@ fn main() {
     let file_contents = {
         let mut file_contents = Vec::with_capacity(args.files.len());
         for f in args.files.iter() {
-            let content = match fs::read_to_string(&f.0) {
+            let content = match f.0.as_str() {
+                STDIO => {
+                    let mut content = String::new();
+                    io::stdin().read_to_string(&mut content).map(|_| content)
+                }
+                path => fs::read_to_string(path),
+            };
+            let content = match content {
                 Ok(content) => content,
                 Err(error) => TdmError::ReadFile {
                     path: f.0.clone(),
@@ -41,7 +53,10 @@ fn main() {
     for ((file, additional_data), content) in file_contents {
         logger.loading(format!("Parsing table '{file}'..."));
         let file_path = Path::new(&file);
-        let file_stem = file_path.file_stem().and_then(OsStr::to_str);
+        let file_stem = match file.as_str() {
+            STDIO => Some("stdin"),
+            _ => file_path.file_stem().and_then(OsStr::to_str),
+        };
         let file_stem = match file_stem {
             None => TdmError::DetermineFileStem {
                 file: file.to_owned(),
@@ -49,23 +64,53 @@ fn main() {
             .handle(&mut logger),
             Some(file_stem) => file_stem,
         };
-        let file_type = file_path.extension().and_then(OsStr::to_str);
+        let (input_options, additional_data) = match InputOptions::extract(additional_data) {
+            Ok(extracted) => extracted,
+            Err(error) => TdmError::ParseTable(error).handle(&mut logger),
+        };
+        let additional_data = &additional_data;
+        let content = input_options.slice(&content);
+        let extension = file_path.extension().and_then(OsStr::to_str);
+        let extension_type = match extension {
+            Some("txt" | "dat") => Some(InTypes::Dat),
+            Some("json") => Some(InTypes::Json),
+            Some("ndjson" | "jsonl") => Some(InTypes::Ndjson),
+            Some("csv") => Some(InTypes::Csv),
+            Some("m") => Some(InTypes::M),
+            Some("md") => Some(InTypes::Md),
+            Some("tex") => Some(InTypes::Tex),
+            _ => None,
+        };
+        let file_type = match (args.from, extension_type) {
+            (Some(file_type), _) | (None, Some(file_type)) => file_type,
+            (None, None) => match (detect_type(&content), extension) {
+                (Some(file_type), _) => file_type,
+                (None, Some(extension)) => TdmError::UnknownFileType {
+                    file_type: extension.to_owned(),
+                }
+                .handle(&mut logger),
+                (None, None) => TdmError::DetermineFileType {
+                    file: file.to_owned(),
+                }
+                .handle(&mut logger),
+            },
+        };
+        // the separator of delimited text is only sniffed without a known extension
+        let sniff = extension_type.is_none();
         let parse_res = match file_type {
-            Some("txt" | "dat") => Table::from_dat(&content, additional_data),
-            Some("json") => Table::from_json(&content, additional_data),
-            Some("csv") => Table::from_csv(&content, additional_data),
-            Some("m") => Table::from_m(&content, additional_data),
-            Some(file_type) => TdmError::UnknownFileType {
-                file_type: file_type.to_owned(),
-            }
-            .handle(&mut logger),
-            None => TdmError::DetermineFileType {
-                file: file.to_owned(),
-            }
-            .handle(&mut logger),
+            InTypes::Csv => Table::from_csv(&content, additional_data, sniff),
+            InTypes::Dat => Table::from_dat(&content, additional_data, sniff),
+            InTypes::Json => Table::from_json(&content, additional_data),
+            InTypes::Ndjson => Table::from_ndjson(&content, additional_data),
+            InTypes::M => Table::from_m(&content, additional_data),
+            InTypes::Md => Table::from_md(&content, additional_data),
+            InTypes::Tex => Table::from_tex(&content, additional_data),
         };
         match parse_res {
-            Ok(table) => tables.push((file_stem.to_string(), table)),
+            Ok(mut table) => {
+                input_options.apply(&mut table);
+                tables.push((file_stem.to_string(), table))
+            }
             Err(error) => TdmError::ParseTable(error).handle(&mut logger),
         }
     }
@@ -79,6 +124,8 @@ fn main() {
                 OutTypes::Tex => basename += ".tex",
                 OutTypes::Md => basename += ".md",
                 OutTypes::Json => basename += ".json",
+                OutTypes::Ndjson => basename += ".ndjson",
+                OutTypes::M => basename += ".m",
             }
             String::from("tdm_") + basename.as_str()
         }
@@ -92,19 +139,72 @@ fn main() {
     let mut tables = tables.into_iter().map(|(_, table)| table);
     let mut first_table = tables.next().unwrap(); // infallible
 
-    match args.vertical {
-        false => {
+    match (args.vertical, args.join_on) {
+        (false, None) => {
             for table in tables {
                 first_table.concat(table);
             }
         }
-        true => {
+        (false, Some(key)) => {
+            for table in tables {
+                first_table.join(table, key, args.join, args.header);
+            }
+        }
+        (true, _) => {
             for table in tables {
-                first_table.stack(table);
+                match args.header {
+                    false => first_table.stack(table),
+                    true => first_table.stack_by_header(table),
+                }
             }
         }
     }
 
+    if args.transpose {
+        first_table.transpose();
+    }
+
+    if !args.compute.is_empty() {
+        logger.loading("Computing columns...");
+    }
+    for computation in args.compute.iter() {
+        first_table
+            .compute(computation, args.header, args.strict)
+            .unwrap_or_else(|(row, error)| {
+                TdmError::ComputeColumn {
+                    name: computation.name.clone(),
+                    row,
+                    error,
+                }
+                .handle(&mut logger)
+            });
+    }
+
+    if !args.filter.is_empty() {
+        logger.loading("Filtering rows...");
+        first_table
+            .filter(&args.filter, args.header, args.strict)
+            .unwrap_or_else(|(row, error)| TdmError::FilterRows { row, error }.handle(&mut logger));
+    }
+
+    if !args.group_by.is_empty() {
+        logger.loading("Grouping rows...");
+        let aggregates = match args.aggregate.is_empty() {
+            true => vec![(Aggregate::Count, None)],
+            false => args.aggregate.clone(),
+        };
+        first_table.group(&args.group_by, &aggregates, args.header);
+    }
+
+    if !args.sort_by.is_empty() {
+        logger.loading("Sorting table...");
+        first_table.sort(&args.sort_by, args.blanks, args.header);
+    }
+
+    if !args.uncertainty.is_empty() {
+        first_table.pair_uncertainties(&args.uncertainty);
+    }
+
     logger.loading("Formatting table...");
     let output = match args.to {
         OutTypes::Csv => first_table.to_csv(&format_options),
@@ -112,8 +212,24 @@ fn main() {
         OutTypes::Tex => first_table.to_tex(&format_options),
         OutTypes::Md => first_table.to_md(&format_options),
         OutTypes::Json => first_table.to_json(&format_options),
+        OutTypes::Ndjson => first_table.to_ndjson(&format_options),
+        OutTypes::M => first_table.to_m(&format_options),
     };
 
+    if out_path == Path::new(STDIO) {
+        logger.done();
+        io::stdout()
+            .write_all(output.as_bytes())
+            .unwrap_or_else(|error| {
+                TdmError::WriteFile {
+                    path: String::from("stdout"),
+                    error,
+                }
+                .handle(&mut logger)
+            });
+        return;
+    }
+
     fs::write(out_path, output).unwrap_or_else(|error| {
         TdmError::WriteFile {
             path: out_path
@@ -1,5 +1,6 @@
-use crate::cli::DecimalSeparator;
+use crate::cli::{DecimalSeparator, Notation, UncertaintyStyle};
 use crate::table::FormatOptions;
+use crate::util::Selector;
 use format_num::NumberFormat;
 use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
@@ -8,11 +9,14 @@ use std::str::FromStr;
 ///
 /// The `Cell` enum represents a single element in a table.
 /// The numerical options allow for more precise formatting of these values.
+/// Values with an uncertainty are only created right before formatting, see
+/// [`Table::pair_uncertainties`](crate::table::Table::pair_uncertainties).
 #[derive(Default, Clone)]
 pub enum Cell {
     Int(i32),
     Float(f64),
     Str(String),
+    Uncertain(f64, f64),
 
     #[default]
     Blank,
@@ -22,89 +26,284 @@ impl Cell {
     /// Formats the cell content as a string to be used in a table.
     ///
     /// The method uses the provided format options from the command-line interface.
-    /// The index is used to check if some cells should get custom formatting.
+    /// The row and column are used to check if some cells should get custom formatting.
     ///
     /// # Arguments
     ///
     /// * `format_options` - A reference to the format options specified by the user.
-    /// * `index` - The index of the cell in the table.
+    /// * `row` - The row index of the cell in the table.
+    /// * `column` - The column index of the cell in the table.
     ///
     /// # Returns
     ///
     /// A `String` containing the formatted cell content.
-    pub fn fmt(&self, format_options: &FormatOptions, index: usize) -> String {
-        // The prefix and suffix are selected from the format options, checking if the index is
-        // within one of the rules.
+    pub fn fmt(&self, format_options: &FormatOptions, row: usize, column: usize) -> String {
+        // Every formatting option is selected from the rules of the format options, checking if
+        // the cell is selected by one of the rules.
         // Later rules can potentially override previously evaluated rules.
-
-        // Prepare the prefix by iterating through the format options' prefix rules.
-        // Most cells are expected to be numerical, so there's no need to test for cell type here.
-        let mut prefix = "";
-        for (range, prefix_str) in format_options.prefix.iter() {
-            if range.contains(&index) {
-                prefix = prefix_str;
-            }
-        }
-
-        // Prepare the suffix by iterating through the format options' suffix rules.
         // Most cells are expected to be numerical, so there's no need to test for cell type here.
-        let mut suffix = "";
-        for (range, suffix_str) in format_options.suffix.iter() {
-            if range.contains(&index) {
-                suffix = suffix_str;
-            }
-        }
+        let prefix = select(&format_options.prefix, row, column).map_or("", String::as_str);
+        let suffix = select(&format_options.suffix, row, column).map_or("", String::as_str);
+        let num_format = NumFormat {
+            precision: select(&format_options.precision, row, column).copied(),
+            significant: select(&format_options.significant, row, column).map(|s| s.get()),
+            notation: select(&format_options.notation, row, column)
+                .copied()
+                .unwrap_or_default(),
+            sign: select(&format_options.sign, row, column)
+                .copied()
+                .unwrap_or(false),
+            decimal_sep: select(&format_options.decimal_sep, row, column)
+                .copied()
+                .unwrap_or_default(),
+        };
 
         // Format the cell content based on its type, adding the prefix and suffix as necessary.
+        // SI notation separates the SI prefix by a space, which is dropped without suffix.
+        let fmt_num = |formatted: String| match suffix.is_empty() {
+            true => prefix.to_string() + formatted.trim_end(),
+            false => prefix.to_string() + formatted.as_str() + suffix,
+        };
         match self {
-            Cell::Int(v) => {
-                prefix.to_string() + Cell::fmt_num(*v, format_options).as_str() + suffix
-            }
-            Cell::Float(v) => {
-                prefix.to_string() + Cell::fmt_num(*v, format_options).as_str() + suffix
+            Cell::Int(v) => fmt_num(Cell::fmt_num(*v, &num_format)),
+            Cell::Float(v) => fmt_num(Cell::fmt_num(*v, &num_format)),
+            Cell::Uncertain(v, u) => {
+                prefix.to_string()
+                    + Cell::fmt_uncertain(*v, *u, &num_format, format_options).as_str()
+                    + suffix
             }
             Cell::Str(s) => s.to_owned(),
             Cell::Blank => String::from(""),
         }
     }
 
+    /// Returns the value of numerical cells as float.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Cell::Int(v) => Some(f64::from(*v)),
+            Cell::Float(v) | Cell::Uncertain(v, _) => Some(*v),
+            Cell::Str(_) | Cell::Blank => None,
+        }
+    }
+
     /// Formats numerical cell values with the given format options.
     ///
     /// # Arguments
     ///
     /// * `value` - The numerical value of the cell.
-    /// * `format_options` - A reference to the format options specified by the user.
+    /// * `num_format` - The number formatting selected for the cell.
     ///
     /// # Returns
     ///
     /// A `String` containing the formatted numerical cell value.
-    fn fmt_num<T>(value: T, format_options: &FormatOptions) -> String
+    fn fmt_num<T>(value: T, num_format: &NumFormat) -> String
     where
         T: Into<f64> + Display + PartialOrd + Copy,
     {
         let nf = NumberFormat::new();
+        let NumFormat {
+            precision,
+            significant,
+            ..
+        } = *num_format;
+        let formatted = match (num_format.notation, significant, precision) {
+            (Notation::Decimal, Some(s), _) => fmt_significant(value.into(), s),
+            (Notation::Decimal, None, Some(p)) => nf.format(format!("0.{p}f").as_str(), value),
+            (Notation::Decimal, None, None) => value.to_string(),
+            (Notation::Scientific, Some(s), _) => nf.format(format!(".{}e", s - 1).as_str(), value),
+            (Notation::Scientific, None, Some(p)) => nf.format(format!(".{p}e").as_str(), value),
+            (Notation::Scientific, None, None) => nf.format("e", value),
+            (Notation::Engineering, ..) => {
+                let (mantissa, exponent) = fmt_engineering(value.into(), significant, precision);
+                format!("{mantissa}e{exponent:+03}")
+            }
+            (Notation::Si, ..) => {
+                let (mantissa, exponent) = fmt_engineering(value.into(), significant, precision);
+                match usize::try_from(exponent / 3 + 8)
+                    .ok()
+                    .and_then(|i| SI_PREFIXES.get(i))
+                {
+                    Some(prefix) => format!("{mantissa} {prefix}"),
+                    None => format!("{mantissa}e{exponent:+03}"),
+                }
+            }
+        };
+        let number: f64 = value.into();
+        let positive = !number.is_nan() && number >= 0.0 && !formatted.starts_with('-');
+        let formatted = match num_format.sign && positive {
+            true => String::from("+") + &formatted,
+            false => formatted,
+        };
+
+        match num_format.decimal_sep {
+            DecimalSeparator::Dot => formatted,
+            DecimalSeparator::Comma => formatted.replace('.', ","),
+        }
+    }
+
+    /// Formats a value with its uncertainty.
+    ///
+    /// The uncertainty is rounded to the number of significant digits given in the format
+    /// options, by default two if its leading digit is 1 and one otherwise.
+    /// The value is rounded to the same decimal place, ignoring the precision.
+    /// With scientific or engineering notation, value and uncertainty share the exponent of the
+    /// value, SI prefixes are written as exponents as well.
+    /// The notation depends on the uncertainty style, LaTeX output uses `\pm` or siunitx.
+    fn fmt_uncertain(
+        value: f64,
+        uncertainty: f64,
+        num_format: &NumFormat,
+        format_options: &FormatOptions,
+    ) -> String {
+        let uncertainty = uncertainty.abs();
+        let leading = match value == 0.0 {
+            false => magnitude(value),
+            true => magnitude(uncertainty),
+        };
+        let exponent = match num_format.notation {
+            Notation::Decimal => 0,
+            Notation::Scientific => leading,
+            Notation::Engineering | Notation::Si => leading.div_euclid(3) * 3,
+        };
+        let value = value / 10f64.powi(exponent);
+        let uncertainty = uncertainty / 10f64.powi(exponent);
+
+        let digits = match format_options.uncertainty_digits {
+            Some(digits) => i32::from(digits),
+            None if uncertainty / 10f64.powi(magnitude(uncertainty)) < 2.0 => 2,
+            None => 1,
+        };
+        let mut decimals = digits - 1 - magnitude(uncertainty);
+        // rounding up may add a digit, e.g. 0.096 to 0.10
+        if magnitude(round(uncertainty, decimals)) > magnitude(uncertainty) {
+            decimals -= 1;
+        }
+        let value = round(value, decimals);
+        let uncertainty = round(uncertainty, decimals);
+
+        let places = decimals.max(0) as usize;
+        let sign = match num_format.sign && value >= 0.0 && value.is_sign_positive() {
+            true => "+",
+            false => "",
+        };
+        let v = format!("{sign}{value:.places$}");
+        let u = format!("{uncertainty:.places$}");
+        let e = format!("e{exponent:+03}");
+
         let formatted = match (
-            format_options.precision,
-            format_options.exponent,
-            format_options.sign,
+            format_options.uncertainty_style,
+            format_options.latex,
+            num_format.notation != Notation::Decimal,
         ) {
-            (None, false, true) if value.into() >= 0.0 => format!("+{value}"),
-            (None, false, _) => value.to_string(),
-            (None, true, false) => nf.format("e", value),
-            (None, true, true) => nf.format("+e", value),
-            (Some(p), false, false) => nf.format(format!("0.{p}f").as_str(), value),
-            (Some(p), false, true) => nf.format(format!("+0.{p}f").as_str(), value),
-            (Some(p), true, false) => nf.format(format!(".{p}e").as_str(), value),
-            (Some(p), true, true) => nf.format(format!("+.{p}e").as_str(), value),
+            (UncertaintyStyle::Paren, _, exponent) => {
+                let u = format!("{:.0}", uncertainty * 10f64.powi(decimals.max(0)));
+                match exponent {
+                    false => format!("{v}({u})"),
+                    true => format!("{v}({u}){e}"),
+                }
+            }
+            (UncertaintyStyle::Siunitx, true, false) => format!("\\num{{{v} +- {u}}}"),
+            (UncertaintyStyle::Siunitx, true, true) => format!("\\num{{{v} +- {u} {e}}}"),
+            (_, true, false) => format!("${v} \\pm {u}$"),
+            (_, true, true) => format!("$({v} \\pm {u}) \\times 10^{{{exponent}}}$"),
+            (_, false, false) => format!("{v} ± {u}"),
+            (_, false, true) => format!("({v} ± {u}){e}"),
         };
 
-        match format_options.decimal_sep {
+        match num_format.decimal_sep {
             DecimalSeparator::Dot => formatted,
             DecimalSeparator::Comma => formatted.replace('.', ","),
         }
     }
 }
 
+/// The number formatting of a single cell, selected from the rules of the format options.
+#[derive(Debug, Clone, Copy)]
+struct NumFormat {
+    precision: Option<u16>,
+    significant: Option<u16>,
+    notation: Notation,
+    sign: bool,
+    decimal_sep: DecimalSeparator,
+}
+
+/// Selects the value of the last rule whose selector contains the cell.
+fn select<T>(rules: &[(Selector, T)], row: usize, column: usize) -> Option<&T> {
+    rules
+        .iter()
+        .rev()
+        .find(|(selector, _)| selector.contains(row, column))
+        .map(|(_, value)| value)
+}
+
+/// Returns the decimal exponent of the leading digit, e.g. -2 for 0.042.
+fn magnitude(value: f64) -> i32 {
+    value.abs().log10().floor() as i32
+}
+
+/// Rounds a value to the given number of decimal places, which may be negative.
+fn round(value: f64, decimals: i32) -> f64 {
+    shift(shift(value, decimals).round(), -decimals)
+}
+
+/// Multiplies a value by a power of ten, dividing for negative exponents to use exact powers.
+///
+/// Tiny values are scaled by more than 1e308, which overflows, so large exponents are split.
+fn shift(value: f64, exponent: i32) -> f64 {
+    if exponent.abs() > 300 {
+        return shift(shift(value, exponent / 2), exponent - exponent / 2);
+    }
+    match exponent >= 0 {
+        true => value * 10f64.powi(exponent),
+        false => value / 10f64.powi(-exponent),
+    }
+}
+
+/// The SI prefixes from 1e-24 to 1e24 in steps of three.
+const SI_PREFIXES: [&str; 17] = [
+    "y", "z", "a", "f", "p", "n", "µ", "m", "", "k", "M", "G", "T", "P", "E", "Z", "Y",
+];
+
+/// Formats a value in engineering notation, returning the mantissa and its exponent.
+///
+/// The exponent is a multiple of three, so the mantissa is at least 1 and below 1000.
+/// The mantissa is rounded to significant digits or decimal places if given.
+fn fmt_engineering(value: f64, significant: Option<u16>, precision: Option<u16>) -> (String, i32) {
+    let fmt_mantissa = |mantissa: f64| match (significant, precision) {
+        (Some(s), _) => fmt_significant(mantissa, s),
+        (None, Some(p)) => format!("{mantissa:.*}", usize::from(p)),
+        // scaling may introduce small errors, e.g. 1.2000000000000002
+        (None, None) => round(mantissa, 12).to_string(),
+    };
+    if value == 0.0 || !value.is_finite() {
+        return (fmt_mantissa(value), 0);
+    }
+
+    let mut exponent = magnitude(value).div_euclid(3) * 3;
+    loop {
+        let mantissa = fmt_mantissa(shift(value, -exponent));
+        // rounding up may reach the next exponent, e.g. 999.96 to 1000.0
+        match mantissa.trim_start_matches('-').parse::<f64>() {
+            Ok(m) if m >= 1000.0 => exponent += 3,
+            _ => return (mantissa, exponent),
+        }
+    }
+}
+
+/// Formats a value rounded to significant digits in decimal notation, e.g. 0.0123 or 12300.
+fn fmt_significant(value: f64, digits: u16) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return format!("{value:.*}", usize::from(digits - 1));
+    }
+
+    let mut decimals = i32::from(digits) - 1 - magnitude(value);
+    // rounding up may add a digit, e.g. 9.96 to 10.0
+    if round(value, decimals).abs() >= shift(1.0, magnitude(value) + 1) {
+        decimals -= 1;
+    }
+    format!("{:.*}", decimals.max(0) as usize, round(value, decimals))
+}
+
 /// Implementation of the `FromStr` trait for cells.
 ///
 /// This infallible conversion tries to parse numerical values in cells, first attempting to parse
@@ -136,6 +335,24 @@ impl FromStr for Cell {
     }
 }
 
+/// Implementation of the `PartialEq` trait for cells.
+///
+/// Numerical cells are compared by their value, so an integer cell may equal a float cell.
+/// Blank cells are only equal to other blank cells.
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Cell::Int(a), Cell::Int(b)) => a == b,
+            (Cell::Float(a), Cell::Float(b)) => a == b,
+            (Cell::Int(a), Cell::Float(b)) | (Cell::Float(b), Cell::Int(a)) => f64::from(*a) == *b,
+            (Cell::Uncertain(a, u), Cell::Uncertain(b, v)) => a == b && u == v,
+            (Cell::Str(a), Cell::Str(b)) => a == b,
+            (Cell::Blank, Cell::Blank) => true,
+            _ => false,
+        }
+    }
+}
+
 /// Custom implementation of the `Debug` trait for `Cell`.
 ///
 /// This implementation provides a simpler representation of the cell content and its type.
@@ -144,8 +361,139 @@ impl Debug for Cell {
         match self {
             Self::Int(v) => write!(f, "{v}i"),
             Self::Float(v) => write!(f, "{v}f"),
+            Self::Uncertain(v, u) => write!(f, "{v}±{u}"),
             Self::Str(v) => write!(f, "{v}"),
             Self::Blank => write!(f, "[]"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{DecimalSeparator, Notation};
+    use crate::table::cell::Cell;
+    use crate::table::FormatOptions;
+    use crate::util::Selector;
+    use std::num::NonZeroU16;
+
+    #[test]
+    fn fmt_significant() {
+        let digits = |d| NonZeroU16::new(d).unwrap();
+        let mut format_options = FormatOptions {
+            precision: vec![(Selector::ALL, 1)],
+            significant: vec![
+                ("1..".parse().unwrap(), digits(3)),
+                ("2".parse().unwrap(), digits(1)),
+            ],
+            ..Default::default()
+        };
+        let fmt =
+            |cell: Cell, index, format_options: &FormatOptions| cell.fmt(format_options, 0, index);
+
+        assert_eq!(fmt(Cell::Float(0.012345), 0, &format_options), "0.0");
+        assert_eq!(fmt(Cell::Float(0.012345), 1, &format_options), "0.0123");
+        assert_eq!(fmt(Cell::Float(9.996), 1, &format_options), "10.0");
+        assert_eq!(fmt(Cell::Int(123456), 1, &format_options), "123000");
+        assert_eq!(fmt(Cell::Float(-0.96), 2, &format_options), "-1");
+        let subnormal = format!("0.{}100", "0".repeat(319));
+        assert_eq!(fmt(Cell::Float(1e-320), 1, &format_options), subnormal);
+        let tiny = format!("0.{}100", "0".repeat(309));
+        assert_eq!(fmt(Cell::Float(1e-310), 1, &format_options), tiny);
+
+        format_options.sign = vec![(Selector::ALL, true)];
+        format_options.decimal_sep = vec![(Selector::ALL, DecimalSeparator::Comma)];
+        assert_eq!(fmt(Cell::Float(1.2345), 1, &format_options), "+1,23");
+
+        format_options.notation = vec![(Selector::ALL, Notation::Scientific)];
+        assert_eq!(fmt(Cell::Float(12345.0), 1, &format_options), "+1,23e+04");
+    }
+
+    #[test]
+    fn fmt_sign() {
+        let format_options = FormatOptions {
+            sign: vec![(Selector::ALL, true)],
+            ..Default::default()
+        };
+        let formatted: Vec<String> = [1.5, 0.0, -0.0, -1.5, f64::NAN, f64::INFINITY]
+            .into_iter()
+            .map(|f| Cell::Float(f).fmt(&format_options, 0, 0))
+            .collect();
+        assert_eq!(formatted, ["+1.5", "+0", "-0", "-1.5", "NaN", "+inf"]);
+
+        let formatted: Vec<String> = [(1.5, 0.1), (-0.001, 0.1), (f64::NAN, 0.1)]
+            .into_iter()
+            .map(|(v, u)| Cell::Uncertain(v, u).fmt(&format_options, 0, 0))
+            .collect();
+        assert_eq!(formatted, ["+1.50 ± 0.10", "-0.00 ± 0.10", "NaN ± 0.10"]);
+    }
+
+    #[test]
+    fn fmt_engineering_and_si() {
+        let mut format_options = FormatOptions {
+            notation: vec![(Selector::ALL, Notation::Engineering)],
+            suffix: vec![("1".parse().unwrap(), String::from("V"))],
+            ..Default::default()
+        };
+        let fmt =
+            |cell: Cell, index, format_options: &FormatOptions| cell.fmt(format_options, 0, index);
+
+        assert_eq!(fmt(Cell::Float(1.2e-6), 0, &format_options), "1.2e-06");
+        assert_eq!(fmt(Cell::Int(-12345), 0, &format_options), "-12.345e+03");
+        assert_eq!(fmt(Cell::Float(0.0), 0, &format_options), "0e+00");
+
+        format_options.precision = vec![(Selector::ALL, 1)];
+        assert_eq!(fmt(Cell::Float(999.96), 0, &format_options), "1.0e+03");
+
+        format_options.notation = vec![(Selector::ALL, Notation::Si)];
+        format_options.precision = vec![];
+        assert_eq!(fmt(Cell::Float(1.2e-6), 0, &format_options), "1.2 µ");
+        assert_eq!(fmt(Cell::Float(1.2e-6), 1, &format_options), "1.2 µV");
+        assert_eq!(fmt(Cell::Float(12.0), 0, &format_options), "12");
+        assert_eq!(fmt(Cell::Float(12.0), 1, &format_options), "12 V");
+        assert_eq!(fmt(Cell::Float(1.2e30), 0, &format_options), "1.2e+30");
+
+        format_options.significant = vec![("..".parse().unwrap(), NonZeroU16::new(2).unwrap())];
+        format_options.sign = vec![(Selector::ALL, true)];
+        assert_eq!(fmt(Cell::Int(12345), 1, &format_options), "+12 kV");
+    }
+
+    #[test]
+    fn fmt_rules() {
+        fn rule<T>(selector: &str, value: T) -> (Selector, T) {
+            (selector.parse().unwrap(), value)
+        }
+        let format_options = FormatOptions {
+            precision: vec![rule("..", 3), rule("0", 0)],
+            notation: vec![rule("2..", Notation::Scientific)],
+            sign: vec![rule("1..", true), rule("2", false)],
+            decimal_sep: vec![rule("1", DecimalSeparator::Comma)],
+            ..Default::default()
+        };
+        let cells = [Cell::Int(12), Cell::Float(1.2345), Cell::Float(0.00012)];
+        let formatted: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(i, c)| c.fmt(&format_options, 0, i))
+            .collect();
+        assert_eq!(formatted, ["12", "+1,234", "1.200e-04"]);
+    }
+
+    #[test]
+    fn fmt_selectors() {
+        let format_options = FormatOptions {
+            precision: vec![
+                ("..".parse().unwrap(), 1),
+                ("r0".parse().unwrap(), 0),
+                ("r1..c2".parse().unwrap(), 2),
+            ],
+            suffix: vec![("r2c1".parse().unwrap(), String::from(" V"))],
+            ..Default::default()
+        };
+        let cell = Cell::Float(1.2345);
+        let formatted: Vec<String> = [(0, 2), (1, 1), (1, 2), (2, 1)]
+            .into_iter()
+            .map(|(row, column)| cell.fmt(&format_options, row, column))
+            .collect();
+        assert_eq!(formatted, ["1", "1.2", "1.23", "1.2 V"]);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::table::cell::Cell;
+use crate::table::expr::{Computation, ExprError, Value};
+use crate::table::Table;
+
+impl Table {
+    /// Appends a column holding the results of the computation for every row.
+    ///
+    /// If `header` is set, column names are resolved against the first row and the name of the
+    /// computation is appended to it.
+    /// Boolean results, e.g. of comparisons, are written as 1 or 0.
+    /// Rows whose evaluation fails, e.g. by multiplying a string, get a blank cell unless `strict`
+    /// is set, in which case the 0-indexed row and the error are returned.
+    pub fn compute(
+        &mut self,
+        computation: &Computation,
+        header: bool,
+        strict: bool,
+    ) -> Result<(), (Option<usize>, ExprError)> {
+        let mut expr = computation.expr.clone();
+        let header_row = match header {
+            true => self.values.front().map(Vec::as_slice),
+            false => None,
+        };
+        expr.resolve(header_row).map_err(|error| (None, error))?;
+
+        let width = self.width;
+        for (i, row) in self.values.iter_mut().enumerate() {
+            let cell = match (header, i) {
+                (true, 0) => Cell::Str(computation.name.clone()),
+                _ => match expr.eval(row) {
+                    Ok(Value::Cell(cell)) => cell,
+                    Ok(Value::Bool(b)) => Cell::Int(i32::from(b)),
+                    Err(error) if strict => return Err((Some(i), error)),
+                    Err(_) => Cell::Blank,
+                },
+            };
+            row.resize(width, Cell::Blank);
+            row.push(cell);
+        }
+        self.width += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::expr::Computation;
+    use crate::table::tests::cells;
+    use crate::table::Table;
+
+    #[test]
+    fn compute_columns() {
+        let raw = "U\tI\n2\t3\n0.5\t\n-4\t0.25\nx\t1";
+        let mut table = Table::from_dat(raw, &None, false).unwrap();
+        for computation in ["P = U * I", "`|P|` = abs(P) + 2^3 / 4", "Q = sqrt(-U) > 1"] {
+            let computation: Computation = computation.parse().unwrap();
+            table.compute(&computation, true, false).unwrap();
+        }
+        assert_eq!(table.width, 5);
+        assert_eq!(
+            cells(&table),
+            [
+                ["U", "I", "P", "|P|", "Q"],
+                ["2i", "3i", "6i", "8f", "[]"],
+                ["0.5f", "[]", "[]", "[]", "[]"],
+                ["-4i", "0.25f", "-1f", "3f", "1i"],
+                ["x", "1i", "[]", "[]", "[]"]
+            ]
+        );
+
+        let computation: Computation = "P = c0 * c1".parse().unwrap();
+        let mut table = Table::from_dat(raw, &None, false).unwrap();
+        let (row, error) = table.compute(&computation, true, true).unwrap_err();
+        assert_eq!(row, Some(4));
+        assert_eq!(error.to_string(), "type mismatch in ''x' * 1'");
+    }
+
+    #[test]
+    fn compute_booleans() {
+        let mut table = Table::from_dat("2\n-4\nx", &None, false).unwrap();
+        let computation: Computation = "B = c0 > 0 && c0 != 3".parse().unwrap();
+        table.compute(&computation, false, false).unwrap();
+        assert_eq!(cells(&table), [["2i", "1i"], ["-4i", "0i"], ["x", "[]"]]);
+    }
+
+    #[test]
+    fn parse_computations() {
+        let error = |s: &str| s.parse::<Computation>().unwrap_err().to_string();
+        assert_eq!(
+            error("c0 == 1"),
+            "expected a column definition like 'P = c1 * c2'"
+        );
+        assert_eq!(error("P = c0 *"), "unexpected end at position 8");
+        assert_eq!(error("P = log(c0)"), "unknown function 'log' at position 4");
+        assert_eq!(
+            error("P = pow(c0)"),
+            "function 'pow' at position 4 expects 2 argument(s)"
+        );
+    }
+}
@@ -0,0 +1,828 @@
+use crate::table::cell::Cell;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// An expression evaluated against the cells of a row.
+///
+/// Columns are referenced by their 0-indexed position (e.g. 'c2') or, if the table has a header,
+/// by their name.
+/// Names that are no valid identifiers can be quoted with backticks (e.g. '`Time [s]`').
+/// Strings are quoted with `'` or `"`, the empty string compares equal to blank cells.
+///
+/// Supported operators, from lowest to highest precedence:
+/// - `||`
+/// - `&&`
+/// - `==`, `!=`, `<`, `<=`, `>`, `>=`
+/// - `+`, `-`
+/// - `*`, `/`
+/// - `!`, unary `-`
+/// - `^`, right associative
+///
+/// Additionally the functions `sqrt`, `ln`, `exp`, `abs` and `pow` are available.
+/// Arithmetic on blank cells results in a blank cell, as do results that are not finite.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Literal(Value),
+    Column(Column),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+/// A reference to a column.
+#[derive(Debug, Clone)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl BinaryOp {
+    fn is_arithmetic(self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Pow
+        )
+    }
+}
+
+impl Display for BinaryOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            BinaryOp::Or => "||",
+            BinaryOp::And => "&&",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Pow => "^",
+        };
+        write!(f, "{op}")
+    }
+}
+
+/// A math function callable in expressions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    Sqrt,
+    Ln,
+    Exp,
+    Abs,
+    Pow,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Function> {
+        match name {
+            "sqrt" => Some(Function::Sqrt),
+            "ln" => Some(Function::Ln),
+            "exp" => Some(Function::Exp),
+            "abs" => Some(Function::Abs),
+            "pow" => Some(Function::Pow),
+            _ => None,
+        }
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Function::Pow => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Function::Sqrt => "sqrt",
+            Function::Ln => "ln",
+            Function::Exp => "exp",
+            Function::Abs => "abs",
+            Function::Pow => "pow",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The result of evaluating an expression.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Cell(Cell),
+    Bool(bool),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Cell(Cell::Str(s)) => write!(f, "'{s}'"),
+            Value::Cell(Cell::Blank) => write!(f, "blank"),
+            Value::Cell(c) => write!(f, "{}", c.as_f64().expect("numerical cell")),
+            Value::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+impl Expr {
+    /// Resolves column names to their index using the header row.
+    pub fn resolve(&mut self, header: Option<&[Cell]>) -> Result<(), ExprError> {
+        match self {
+            Expr::Literal(_) | Expr::Column(Column::Index(_)) => Ok(()),
+            Expr::Column(Column::Name(name)) => {
+                let index = header.and_then(|h| {
+                    h.iter()
+                        .position(|c| matches!(c, Cell::Str(s) if s == name))
+                });
+                match index {
+                    Some(index) => {
+                        *self = Expr::Column(Column::Index(index));
+                        Ok(())
+                    }
+                    None => Err(ExprError::UnknownColumn(name.clone())),
+                }
+            }
+            Expr::Not(expr) | Expr::Neg(expr) => expr.resolve(header),
+            Expr::Binary(left, _, right) => {
+                left.resolve(header)?;
+                right.resolve(header)
+            }
+            Expr::Call(_, args) => args.iter_mut().try_for_each(|arg| arg.resolve(header)),
+        }
+    }
+
+    /// Evaluates the expression against a row.
+    ///
+    /// Columns outside of the row are blank, column names must have been resolved before.
+    pub fn eval(&self, row: &[Cell]) -> Result<Value, ExprError> {
+        match self {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Column(Column::Index(i)) => {
+                Ok(Value::Cell(row.get(*i).cloned().unwrap_or_default()))
+            }
+            Expr::Column(Column::Name(name)) => Err(ExprError::UnknownColumn(name.clone())),
+            Expr::Not(expr) => match expr.eval(row)? {
+                Value::Bool(b) => Ok(Value::Bool(!b)),
+                value => Err(ExprError::TypeMismatch(format!("!{value}"))),
+            },
+            Expr::Neg(expr) => match expr.eval(row)? {
+                Value::Cell(Cell::Int(i)) => Ok(Value::Cell(
+                    i.checked_neg()
+                        .map(Cell::Int)
+                        .unwrap_or_else(|| float(-f64::from(i))),
+                )),
+                Value::Cell(Cell::Float(f)) => Ok(Value::Cell(Cell::Float(-f))),
+                Value::Cell(Cell::Blank) => Ok(Value::Cell(Cell::Blank)),
+                value => Err(ExprError::TypeMismatch(format!("-{value}"))),
+            },
+            Expr::Binary(left, op, right) => {
+                let left = left.eval(row)?;
+                // short-circuit logical operators
+                match (op, &left) {
+                    (BinaryOp::Or, Value::Bool(true)) => return Ok(Value::Bool(true)),
+                    (BinaryOp::And, Value::Bool(false)) => return Ok(Value::Bool(false)),
+                    _ => (),
+                }
+                let right = right.eval(row)?;
+                eval_binary(left, *op, right)
+            }
+            Expr::Call(function, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(row))
+                    .collect::<Result<Vec<_>, _>>()?;
+                eval_call(*function, args)
+            }
+        }
+    }
+}
+
+/// Evaluates a function call on its evaluated arguments.
+fn eval_call(function: Function, args: Vec<Value>) -> Result<Value, ExprError> {
+    let mut cells = Vec::with_capacity(args.len());
+    for arg in args.iter() {
+        match arg {
+            Value::Cell(cell @ (Cell::Int(_) | Cell::Float(_))) => cells.push(cell),
+            Value::Cell(Cell::Blank) => (),
+            _ => {
+                let args = itertools::join(args.iter(), ", ");
+                return Err(ExprError::TypeMismatch(format!("{function}({args})")));
+            }
+        }
+    }
+    if cells.len() < args.len() {
+        return Ok(Value::Cell(Cell::Blank));
+    }
+
+    let x = cells[0].as_f64().expect("numerical cell");
+    let cell = match (function, cells[0]) {
+        (Function::Abs, Cell::Int(i)) => i
+            .checked_abs()
+            .map(Cell::Int)
+            .unwrap_or_else(|| float(x.abs())),
+        (Function::Abs, _) => float(x.abs()),
+        (Function::Sqrt, _) => float(x.sqrt()),
+        (Function::Ln, _) => float(x.ln()),
+        (Function::Exp, _) => float(x.exp()),
+        (Function::Pow, _) => arithmetic(cells[0], BinaryOp::Pow, cells[1]),
+    };
+    Ok(Value::Cell(cell))
+}
+
+/// Evaluates an arithmetic operation on two numerical cells.
+///
+/// Operations on integers stay integers unless they overflow, divisions always result in floats.
+fn arithmetic(a: &Cell, op: BinaryOp, b: &Cell) -> Cell {
+    if let (Cell::Int(a), Cell::Int(b)) = (a, b) {
+        let result = match op {
+            BinaryOp::Add => a.checked_add(*b),
+            BinaryOp::Sub => a.checked_sub(*b),
+            BinaryOp::Mul => a.checked_mul(*b),
+            BinaryOp::Pow => u32::try_from(*b).ok().and_then(|b| a.checked_pow(b)),
+            _ => None,
+        };
+        if let Some(result) = result {
+            return Cell::Int(result);
+        }
+    }
+
+    let a = a.as_f64().expect("numerical cell");
+    let b = b.as_f64().expect("numerical cell");
+    float(match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Sub => a - b,
+        BinaryOp::Mul => a * b,
+        BinaryOp::Div => a / b,
+        BinaryOp::Pow => a.powf(b),
+        _ => unreachable!("only called with arithmetic operators"),
+    })
+}
+
+/// Creates a float cell, results that are not finite are blank.
+fn float(value: f64) -> Cell {
+    match value.is_finite() {
+        true => Cell::Float(value),
+        false => Cell::Blank,
+    }
+}
+
+/// Evaluates a binary operation on two values.
+fn eval_binary(left: Value, op: BinaryOp, right: Value) -> Result<Value, ExprError> {
+    use std::cmp::Ordering;
+
+    let mismatch = || ExprError::TypeMismatch(format!("{left} {op} {right}"));
+    if op.is_arithmetic() {
+        let (Value::Cell(a), Value::Cell(b)) = (&left, &right) else {
+            return Err(mismatch());
+        };
+        return match (a, b) {
+            (Cell::Str(_), _) | (_, Cell::Str(_)) => Err(mismatch()),
+            (Cell::Blank, _) | (_, Cell::Blank) => Ok(Value::Cell(Cell::Blank)),
+            (a, b) => Ok(Value::Cell(arithmetic(a, op, b))),
+        };
+    }
+
+    let ordering = match (&left, &right) {
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::Cell(Cell::Blank), Value::Cell(Cell::Blank)) => Some(Ordering::Equal),
+        (Value::Cell(Cell::Str(a)), Value::Cell(Cell::Str(b))) => Some(a.cmp(b)),
+        (Value::Cell(a), Value::Cell(b)) => match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => None,
+        },
+        _ => None,
+    };
+    let blank =
+        matches!(left, Value::Cell(Cell::Blank)) || matches!(right, Value::Cell(Cell::Blank));
+
+    let result = match (op, &left, &right, ordering) {
+        (BinaryOp::Or | BinaryOp::And, Value::Bool(a), Value::Bool(b), _) => match op {
+            BinaryOp::Or => *a || *b,
+            _ => *a && *b,
+        },
+        (BinaryOp::Or | BinaryOp::And, _, _, _) => return Err(mismatch()),
+
+        // blank cells can always be compared for equality
+        (BinaryOp::Eq, _, _, None) if blank => false,
+        (BinaryOp::Ne, _, _, None) if blank => true,
+
+        (_, _, _, None) => return Err(mismatch()),
+        (BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Pow, ..) => {
+            unreachable!("arithmetic operators are handled above")
+        }
+        (BinaryOp::Eq, _, _, Some(o)) => o == Ordering::Equal,
+        (BinaryOp::Ne, _, _, Some(o)) => o != Ordering::Equal,
+        (BinaryOp::Lt, _, _, Some(o)) => o == Ordering::Less,
+        (BinaryOp::Le, _, _, Some(o)) => o != Ordering::Greater,
+        (BinaryOp::Gt, _, _, Some(o)) => o == Ordering::Greater,
+        (BinaryOp::Ge, _, _, Some(o)) => o != Ordering::Less,
+    };
+    Ok(Value::Bool(result))
+}
+
+/// A named expression computing a new column, e.g. 'P = c1 * c2'.
+///
+/// The name may be quoted with backticks.
+#[derive(Debug, Clone)]
+pub struct Computation {
+    pub name: String,
+    pub expr: Expr,
+}
+
+impl FromStr for Computation {
+    type Err = ParseExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, expr) = s.split_once('=').ok_or(ParseExprError::MissingName)?;
+        let name = name.trim();
+        if name.is_empty() || name.ends_with(['!', '<', '>']) || expr.starts_with('=') {
+            return Err(ParseExprError::MissingName);
+        }
+        let name = name
+            .strip_prefix('`')
+            .and_then(|n| n.strip_suffix('`'))
+            .unwrap_or(name);
+
+        let offset = s.len() - expr.len();
+        let expr = expr
+            .parse()
+            .map_err(|e: ParseExprError| e.offset(s[..offset].chars().count()))?;
+        Ok(Computation {
+            name: name.to_owned(),
+            expr,
+        })
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ParseExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            len: s.chars().count(),
+        };
+        let expr = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some((position, token)) => Err(ParseExprError::Unexpected {
+                found: token.to_string(),
+                position: *position,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(Cell),
+    Name(String),
+    Column(String),
+    Index(usize),
+    Op(&'static str),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Literal(Cell::Str(s)) => write!(f, "'{s}'"),
+            Token::Literal(Cell::Blank) => write!(f, "''"),
+            Token::Literal(c) => write!(f, "{}", c.as_f64().expect("numerical cell")),
+            Token::Name(name) => write!(f, "{name}"),
+            Token::Column(name) => write!(f, "`{name}`"),
+            Token::Index(i) => write!(f, "c{i}"),
+            Token::Op(op) => write!(f, "{op}"),
+        }
+    }
+}
+
+/// The operators of the expression language, longer operators first.
+const OPERATORS: &[&str] = &[
+    "||", "&&", "==", "!=", "<=", ">=", "<", ">", "!", "+", "-", "*", "/", "^", "(", ")", ",",
+];
+
+/// Splits an expression into tokens with their position.
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, ParseExprError> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        let start = pos;
+        let c = chars[pos];
+        let rest: String = chars[pos..].iter().collect();
+
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            pos += op.chars().count();
+            tokens.push((start, Token::Op(op)));
+            continue;
+        }
+
+        match c {
+            '\'' | '"' | '`' => {
+                let mut value = String::new();
+                pos += 1;
+                loop {
+                    match (chars.get(pos), chars.get(pos + 1)) {
+                        (None, _) => return Err(ParseExprError::Unclosed { position: start }),
+                        (Some(q), Some(n)) if *q == c && *n == c => {
+                            value.push(c);
+                            pos += 2;
+                        }
+                        (Some(q), _) if *q == c => {
+                            pos += 1;
+                            break;
+                        }
+                        (Some(v), _) => {
+                            value.push(*v);
+                            pos += 1;
+                        }
+                    }
+                }
+                let token = match c {
+                    '`' => Token::Column(value),
+                    _ if value.is_empty() => Token::Literal(Cell::Blank),
+                    _ => Token::Literal(Cell::Str(value)),
+                };
+                tokens.push((start, token));
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                while pos < chars.len()
+                    && (chars[pos].is_ascii_alphanumeric()
+                        || chars[pos] == '.'
+                        || (matches!(chars[pos], '+' | '-') && matches!(chars[pos - 1], 'e' | 'E')))
+                {
+                    pos += 1;
+                }
+                let number: String = chars[start..pos].iter().collect();
+                let cell = Cell::from_str(&number).expect("infallible");
+                if !matches!(cell, Cell::Int(_) | Cell::Float(_)) || number.contains(',') {
+                    return Err(ParseExprError::Unexpected {
+                        found: number,
+                        position: start,
+                    });
+                }
+                tokens.push((start, Token::Literal(cell)));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '_') {
+                    pos += 1;
+                }
+                let name: String = chars[start..pos].iter().collect();
+                let index = name
+                    .strip_prefix('c')
+                    .filter(|i| i.chars().all(|c| c.is_ascii_digit()))
+                    .and_then(|i| i.parse().ok());
+                tokens.push((
+                    start,
+                    match index {
+                        Some(index) => Token::Index(index),
+                        None => Token::Name(name),
+                    },
+                ));
+            }
+            c => {
+                return Err(ParseExprError::Unexpected {
+                    found: c.to_string(),
+                    position: start,
+                })
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser over the tokens of an expression.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    len: usize,
+}
+
+impl Parser {
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.tokens.get(self.pos) {
+            Some((_, Token::Op(op))) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn binary(
+        &mut self,
+        ops: &[(&str, BinaryOp)],
+        next: fn(&mut Self) -> Result<Expr, ParseExprError>,
+        chain: bool,
+    ) -> Result<Expr, ParseExprError> {
+        let mut expr = next(self)?;
+        while let Some(op) = self.peek_op() {
+            let Some((_, op)) = ops.iter().find(|(o, _)| *o == op) else {
+                break;
+            };
+            self.pos += 1;
+            expr = Expr::Binary(Box::new(expr), *op, Box::new(next(self)?));
+            if !chain {
+                break;
+            }
+        }
+        Ok(expr)
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseExprError> {
+        self.binary(&[("||", BinaryOp::Or)], Self::and, true)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseExprError> {
+        self.binary(&[("&&", BinaryOp::And)], Self::comparison, true)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseExprError> {
+        let ops = [
+            ("==", BinaryOp::Eq),
+            ("!=", BinaryOp::Ne),
+            ("<", BinaryOp::Lt),
+            ("<=", BinaryOp::Le),
+            (">", BinaryOp::Gt),
+            (">=", BinaryOp::Ge),
+        ];
+        self.binary(&ops, Self::sum, false)
+    }
+
+    fn sum(&mut self) -> Result<Expr, ParseExprError> {
+        let ops = [("+", BinaryOp::Add), ("-", BinaryOp::Sub)];
+        self.binary(&ops, Self::product, true)
+    }
+
+    fn product(&mut self) -> Result<Expr, ParseExprError> {
+        let ops = [("*", BinaryOp::Mul), ("/", BinaryOp::Div)];
+        self.binary(&ops, Self::unary, true)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseExprError> {
+        match self.peek_op() {
+            Some("!") => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some("-") => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Expr, ParseExprError> {
+        let base = self.primary()?;
+        if self.peek_op() != Some("^") {
+            return Ok(base);
+        }
+        self.pos += 1;
+        Ok(Expr::Binary(
+            Box::new(base),
+            BinaryOp::Pow,
+            Box::new(self.unary()?),
+        ))
+    }
+
+    /// Expects the given operator as the next token.
+    fn expect(&mut self, op: &str) -> Result<(), ParseExprError> {
+        match self.tokens.get(self.pos) {
+            Some((_, Token::Op(o))) if *o == op => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some((position, token)) => Err(ParseExprError::Unexpected {
+                found: token.to_string(),
+                position: *position,
+            }),
+            None => Err(ParseExprError::UnexpectedEnd { position: self.len }),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseExprError> {
+        let Some((position, token)) = self.tokens.get(self.pos).cloned() else {
+            return Err(ParseExprError::UnexpectedEnd { position: self.len });
+        };
+        self.pos += 1;
+
+        match token {
+            Token::Literal(cell) => Ok(Expr::Literal(Value::Cell(cell))),
+            Token::Index(i) => Ok(Expr::Column(Column::Index(i))),
+            Token::Name(name) if self.peek_op() == Some("(") => {
+                self.pos += 1;
+                let function = Function::from_name(&name)
+                    .ok_or(ParseExprError::UnknownFunction { name, position })?;
+                let mut args = Vec::new();
+                if self.peek_op() != Some(")") {
+                    args.push(self.or()?);
+                    while self.peek_op() == Some(",") {
+                        self.pos += 1;
+                        args.push(self.or()?);
+                    }
+                }
+                self.expect(")")?;
+                if args.len() != function.arity() {
+                    return Err(ParseExprError::Arguments {
+                        function,
+                        expected: function.arity(),
+                        position,
+                    });
+                }
+                Ok(Expr::Call(function, args))
+            }
+            Token::Name(name) | Token::Column(name) => Ok(Expr::Column(Column::Name(name))),
+            Token::Op("(") => {
+                let expr = self.or()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            token => Err(ParseExprError::Unexpected {
+                found: token.to_string(),
+                position,
+            }),
+        }
+    }
+}
+
+/// An error that occurs when parsing an expression.
+#[derive(Debug)]
+pub enum ParseExprError {
+    Unexpected {
+        found: String,
+        position: usize,
+    },
+    UnexpectedEnd {
+        position: usize,
+    },
+    Unclosed {
+        position: usize,
+    },
+    UnknownFunction {
+        name: String,
+        position: usize,
+    },
+    Arguments {
+        function: Function,
+        expected: usize,
+        position: usize,
+    },
+    MissingName,
+}
+
+impl ParseExprError {
+    /// Shifts the position of the error, used when the expression is part of a longer argument.
+    fn offset(self, offset: usize) -> Self {
+        match self {
+            ParseExprError::Unexpected { found, position } => ParseExprError::Unexpected {
+                found,
+                position: position + offset,
+            },
+            ParseExprError::UnexpectedEnd { position } => ParseExprError::UnexpectedEnd {
+                position: position + offset,
+            },
+            ParseExprError::Unclosed { position } => ParseExprError::Unclosed {
+                position: position + offset,
+            },
+            ParseExprError::UnknownFunction { name, position } => ParseExprError::UnknownFunction {
+                name,
+                position: position + offset,
+            },
+            ParseExprError::Arguments {
+                function,
+                expected,
+                position,
+            } => ParseExprError::Arguments {
+                function,
+                expected,
+                position: position + offset,
+            },
+            ParseExprError::MissingName => ParseExprError::MissingName,
+        }
+    }
+}
+
+impl Display for ParseExprError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseExprError::Unexpected { found, position } => {
+                write!(f, "unexpected '{found}' at position {position}")
+            }
+            ParseExprError::UnexpectedEnd { position } => {
+                write!(f, "unexpected end at position {position}")
+            }
+            ParseExprError::Unclosed { position } => {
+                write!(f, "unclosed quote at position {position}")
+            }
+            ParseExprError::UnknownFunction { name, position } => {
+                write!(f, "unknown function '{name}' at position {position}")
+            }
+            ParseExprError::Arguments {
+                function,
+                expected,
+                position,
+            } => write!(
+                f,
+                "function '{function}' at position {position} expects {expected} argument(s)"
+            ),
+            ParseExprError::MissingName => {
+                write!(f, "expected a column definition like 'P = c1 * c2'")
+            }
+        }
+    }
+}
+
+impl Error for ParseExprError {}
+
+/// An error that occurs when resolving or evaluating an expression.
+#[derive(Debug)]
+pub enum ExprError {
+    UnknownColumn(String),
+    TypeMismatch(String),
+    NotBoolean(String),
+}
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExprError::UnknownColumn(name) => write!(f, "unknown column '{name}'"),
+            ExprError::TypeMismatch(operation) => write!(f, "type mismatch in '{operation}'"),
+            ExprError::NotBoolean(value) => write!(f, "expected a boolean, found '{value}'"),
+        }
+    }
+}
+
+impl Error for ExprError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::cell::Cell;
+    use crate::table::expr::{Expr, Value};
+
+    fn eval(expr: &str, row: &[Cell]) -> String {
+        let mut expr: Expr = expr.parse().unwrap();
+        let header = [
+            Cell::Str(String::from("name")),
+            Cell::Str(String::from("U [V]")),
+        ];
+        expr.resolve(Some(&header)).unwrap();
+        match expr.eval(row) {
+            Ok(Value::Bool(b)) => b.to_string(),
+            Ok(value) => value.to_string(),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn eval_works() {
+        let row = [Cell::Str(String::from("a")), Cell::Float(0.75), Cell::Blank];
+        assert_eq!(eval("c1 > 0.5 && c0 != ''", &row), "true");
+        assert_eq!(eval("`U [V]` <= 0.5 || name == \"a\"", &row), "true");
+        assert_eq!(eval("!(c2 == '') || c9 == ''", &row), "true");
+        assert_eq!(eval("c2 != 1", &row), "true");
+        assert_eq!(eval("c0 > 1", &row), "type mismatch in ''a' > 1'");
+        assert_eq!(eval("c2 < 1", &row), "type mismatch in 'blank < 1'");
+        assert_eq!(eval("-2^2 + 2^3^2 - 10 / 4 * 2", &row), "503");
+        assert_eq!(eval("c1 * 4 + c2 == ''", &row), "true");
+        assert_eq!(eval("pow(c1, 2) - exp(ln(2)) < 0", &row), "true");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |s: &str| s.parse::<Expr>().unwrap_err().to_string();
+        assert_eq!(error("c1 > "), "unexpected end at position 5");
+        assert_eq!(error("c1 > 0.5 c2"), "unexpected 'c2' at position 9");
+        assert_eq!(error("c1 == 'a"), "unclosed quote at position 6");
+        assert_eq!(error("c1 < 2 < 3"), "unexpected '<' at position 7");
+
+        let mut expr: Expr = "missing == 1".parse().unwrap();
+        assert!(expr.resolve(None).is_err());
+    }
+}
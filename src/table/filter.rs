@@ -0,0 +1,97 @@
+use crate::table::expr::{Expr, ExprError, Value};
+use crate::table::Table;
+
+impl Table {
+    /// Keeps only the rows for which all expressions evaluate to true.
+    ///
+    /// If `header` is set, the first row is always kept and column names are resolved against it.
+    /// Rows whose evaluation fails, e.g. by comparing a string with a number, are dropped unless
+    /// `strict` is set, in which case the 0-indexed row and the error are returned.
+    pub fn filter(
+        &mut self,
+        exprs: &[Expr],
+        header: bool,
+        strict: bool,
+    ) -> Result<(), (Option<usize>, ExprError)> {
+        let header_row = match header {
+            true => self.values.pop_front(),
+            false => None,
+        };
+
+        let mut exprs = exprs.to_vec();
+        for expr in exprs.iter_mut() {
+            expr.resolve(header_row.as_deref())
+                .map_err(|error| (None, error))?;
+        }
+
+        let offset = usize::from(header_row.is_some());
+        let mut error = None;
+        let mut index = 0;
+        self.values.retain(|row| {
+            let keep = exprs
+                .iter()
+                .try_fold(true, |keep, expr| match (keep, expr.eval(row)) {
+                    (false, _) => Ok(false),
+                    (true, Ok(Value::Bool(b))) => Ok(b),
+                    (true, Ok(value)) => Err(ExprError::NotBoolean(value.to_string())),
+                    (true, Err(error)) => Err(error),
+                });
+            index += 1;
+            match keep {
+                Ok(keep) => keep,
+                Err(e) => {
+                    if strict && error.is_none() {
+                        error = Some((Some(index - 1 + offset), e));
+                    }
+                    false
+                }
+            }
+        });
+
+        if let Some(header_row) = header_row {
+            self.values.push_front(header_row);
+        }
+        self.height = self.values.len();
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::expr::Expr;
+    use crate::table::tests::cells;
+    use crate::table::Table;
+
+    #[test]
+    fn filter_rows() {
+        let raw = "name\tU\na\t0.25\nb\t0.75\nc\t\nd\tx";
+        let exprs: Vec<Expr> = vec!["U > 0.5 || name == 'a'".parse().unwrap()];
+
+        let mut table = Table::from_dat(raw, &None, false).unwrap();
+        table.filter(&exprs, true, false).unwrap();
+        assert_eq!(
+            cells(&table),
+            [["name", "U"], ["a", "0.25f"], ["b", "0.75f"]]
+        );
+        assert_eq!(table.height, 3);
+
+        let mut table = Table::from_dat(raw, &None, false).unwrap();
+        let (row, error) = table.filter(&exprs, true, true).unwrap_err();
+        assert_eq!(row, Some(3));
+        assert_eq!(error.to_string(), "type mismatch in 'blank > 0.5'");
+
+        let mut table = Table::from_dat(raw, &None, false).unwrap();
+        let (row, _) = table.filter(&exprs, false, false).unwrap_err();
+        assert_eq!(row, None);
+
+        let mut table = Table::from_dat("1\n2", &None, false).unwrap();
+        let (_, error) = table
+            .filter(&["c0".parse().unwrap()], false, true)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "expected a boolean, found '1'");
+    }
+}
@@ -0,0 +1,244 @@
+use crate::cli::Aggregate;
+use crate::table::cell::Cell;
+use crate::table::Table;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+impl Table {
+    /// Groups the rows by the key columns, keeping one row per group.
+    ///
+    /// Every resulting row holds the key columns followed by one column per aggregate, groups are
+    /// ordered by their first appearance.
+    /// Numerical keys are compared by value, all `NaN` keys belong to the same group.
+    /// Aggregates consider only the numerical cells of their column, except for `count` which
+    /// counts the non-blank cells of the column or, without a column, the rows of the group.
+    /// If `header` is set, the first row is replaced by the key names and the aggregate names.
+    pub fn group(
+        &mut self,
+        keys: &[usize],
+        aggregates: &[(Aggregate, Option<usize>)],
+        header: bool,
+    ) {
+        let header_row = match header {
+            true => self.values.pop_front(),
+            false => None,
+        };
+
+        let mut groups: Vec<(Vec<Cell>, Vec<Vec<Cell>>)> = Vec::new();
+        let mut group_indices: HashMap<Vec<GroupKey>, usize> = HashMap::new();
+        for row in self.values.drain(..) {
+            let key: Vec<Cell> = keys
+                .iter()
+                .map(|k| row.get(*k).cloned().unwrap_or_default())
+                .collect();
+            match group_indices.entry(key.iter().map(GroupKey::from).collect()) {
+                Entry::Occupied(entry) => groups[*entry.get()].1.push(row),
+                Entry::Vacant(entry) => {
+                    entry.insert(groups.len());
+                    groups.push((key, vec![row]));
+                }
+            }
+        }
+
+        let mut values: VecDeque<Vec<Cell>> = groups
+            .into_iter()
+            .map(|(mut key, rows)| {
+                key.extend(aggregates.iter().map(|(aggregate, column)| {
+                    let cells: Vec<&Cell> = column
+                        .map(|column| rows.iter().filter_map(|r| r.get(column)).collect())
+                        .unwrap_or_default();
+                    match (aggregate, column) {
+                        (Aggregate::Count, None) => count(rows.len()),
+                        (aggregate, _) => aggregate.apply(&cells),
+                    }
+                }));
+                key
+            })
+            .collect();
+
+        if let Some(header_row) = header_row {
+            let name = |i: &usize| match header_row.get(*i) {
+                Some(Cell::Str(name)) => name.clone(),
+                _ => format!("c{i}"),
+            };
+            let mut names: Vec<Cell> = keys.iter().map(|k| Cell::Str(name(k))).collect();
+            names.extend(aggregates.iter().map(|(aggregate, column)| {
+                Cell::Str(match column {
+                    Some(column) => format!("{aggregate}({})", name(column)),
+                    None => aggregate.to_string(),
+                })
+            }));
+            values.push_front(names);
+        }
+
+        *self = Table::from(values);
+    }
+}
+
+impl Aggregate {
+    /// Aggregates the numerical cells, blank if there are none or too few.
+    ///
+    /// `count` counts all non-blank cells instead.
+    fn apply(&self, cells: &[&Cell]) -> Cell {
+        if let Aggregate::Count = self {
+            return count(cells.iter().filter(|c| !matches!(c, Cell::Blank)).count());
+        }
+
+        let numbers: Vec<f64> = cells.iter().filter_map(|c| c.as_f64()).collect();
+        if numbers.is_empty() {
+            return Cell::Blank;
+        }
+        let n = numbers.len() as f64;
+        let mean = numbers.iter().sum::<f64>() / n;
+        let std = || match numbers.len() {
+            1 => None,
+            _ => Some((numbers.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()),
+        };
+
+        match self {
+            Aggregate::Count => unreachable!("handled above"),
+            Aggregate::Sum => {
+                let ints: Option<Vec<i32>> = cells
+                    .iter()
+                    .filter(|c| c.as_f64().is_some())
+                    .map(|c| match c {
+                        Cell::Int(i) => Some(*i),
+                        _ => None,
+                    })
+                    .collect();
+                ints.and_then(|ints| ints.into_iter().try_fold(0i32, |a, b| a.checked_add(b)))
+                    .map(Cell::Int)
+                    .unwrap_or_else(|| Cell::Float(numbers.iter().sum()))
+            }
+            Aggregate::Mean => Cell::Float(mean),
+            Aggregate::Median => {
+                let mut sorted = numbers.clone();
+                sorted.sort_by(f64::total_cmp);
+                let mid = sorted.len() / 2;
+                Cell::Float(match sorted.len() % 2 {
+                    0 => (sorted[mid - 1] + sorted[mid]) / 2.0,
+                    _ => sorted[mid],
+                })
+            }
+            Aggregate::Min | Aggregate::Max => {
+                let extreme = cells
+                    .iter()
+                    .filter(|c| c.as_f64().is_some())
+                    .reduce(|a, b| {
+                        let ordering = a.as_f64().unwrap().total_cmp(&b.as_f64().unwrap());
+                        match (self, ordering.is_gt()) {
+                            (Aggregate::Min, true) | (Aggregate::Max, false) => b,
+                            _ => a,
+                        }
+                    });
+                (*extreme.expect("numbers are not empty")).clone()
+            }
+            Aggregate::Std => std().map(Cell::Float).unwrap_or_default(),
+            Aggregate::Sem => std()
+                .map(|std| Cell::Float(std / n.sqrt()))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// A hashable representation of a key cell used to find the group of a row or a join partner.
+#[derive(PartialEq, Eq, Hash)]
+pub(super) enum GroupKey {
+    Number(u64),
+    Uncertain(u64, u64),
+    Str(String),
+    Blank,
+}
+
+impl From<&Cell> for GroupKey {
+    fn from(cell: &Cell) -> Self {
+        match cell {
+            Cell::Int(i) => GroupKey::Number(number_bits(f64::from(*i))),
+            Cell::Float(f) => GroupKey::Number(number_bits(*f)),
+            Cell::Uncertain(v, u) => GroupKey::Uncertain(number_bits(*v), number_bits(*u)),
+            Cell::Str(s) => GroupKey::Str(s.clone()),
+            Cell::Blank => GroupKey::Blank,
+        }
+    }
+}
+
+/// Returns the bits of a number, using the same bits for all `NaN`s and both zeros.
+fn number_bits(value: f64) -> u64 {
+    match value {
+        v if v.is_nan() => f64::NAN.to_bits(),
+        0.0 => 0f64.to_bits(),
+        v => v.to_bits(),
+    }
+}
+
+/// Creates a cell holding a count.
+fn count(count: usize) -> Cell {
+    i32::try_from(count)
+        .map(Cell::Int)
+        .unwrap_or(Cell::Float(count as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::Aggregate;
+    use crate::table::tests::cells;
+    use crate::table::Table;
+
+    #[test]
+    fn group_aggregates() {
+        let raw = "f\tU\tname\n1\t2\ta\n2\t4\tb\n1\t4\t\n1\t1.5\tc\n2\tx\td";
+        let aggregates = [
+            (Aggregate::Count, None),
+            (Aggregate::Count, Some(2)),
+            (Aggregate::Sum, Some(1)),
+            (Aggregate::Mean, Some(1)),
+            (Aggregate::Median, Some(1)),
+            (Aggregate::Min, Some(1)),
+            (Aggregate::Max, Some(1)),
+            (Aggregate::Std, Some(1)),
+            (Aggregate::Sem, Some(1)),
+        ];
+
+        let mut table = Table::from_dat(raw, &None, false).unwrap();
+        table.group(&[0], &aggregates, true);
+        assert_eq!((table.height, table.width), (3, 10));
+        assert_eq!(
+            cells(&table),
+            [
+                [
+                    "f",
+                    "count",
+                    "count(name)",
+                    "sum(U)",
+                    "mean(U)",
+                    "median(U)",
+                    "min(U)",
+                    "max(U)",
+                    "std(U)",
+                    "sem(U)"
+                ],
+                [
+                    "1i",
+                    "3i",
+                    "2i",
+                    "7.5f",
+                    "2.5f",
+                    "2f",
+                    "1.5f",
+                    "4i",
+                    "1.3228756555322954f",
+                    "0.7637626158259734f"
+                ],
+                ["2i", "2i", "2i", "4i", "4f", "4f", "4i", "4i", "[]", "[]"]
+            ]
+        );
+    }
+
+    #[test]
+    fn group_nan_keys() {
+        let raw = "nan\t1\n1\t2\nNaN\t3\n1.0\t4\n-0\t5\n0.0\t6";
+        let mut table = Table::from_dat(raw, &None, false).unwrap();
+        table.group(&[0], &[(Aggregate::Sum, Some(1))], false);
+        assert_eq!(cells(&table), [["NaNf", "4i"], ["1i", "6i"], ["0i", "11i"]]);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::table::transform::ParseTableError;
+use crate::table::Table;
+use crate::util::{self, AnyRange};
+use std::borrow::Cow;
+
+/// Options of the additional data that apply to input files of every type.
+///
+/// These options are handled around the `from_x` transformers, all other options of the
+/// additional data are passed on to them.
+/// - `skip=<n>`: skips the first `n` lines
+/// - `drop=<n>`: drops the last `n` non-empty lines
+/// - `rows=<range>`: keeps only these lines, counted after skipping (e.g. `rows=..100`)
+/// - `cols=<ranges>`: comma separated column ranges, only these columns are kept in the given
+///   order (e.g. `cols=0,3..5`)
+/// - `transpose` or `transpose=<true|false>`: transposes the table after selecting columns
+///
+/// The lines are sliced before the transformers build the table, so that preambles or summaries
+/// never become part of it.
+#[derive(Debug, Default)]
+pub struct InputOptions {
+    skip: usize,
+    drop: usize,
+    rows: Option<AnyRange<usize>>,
+    columns: Option<Vec<AnyRange<usize>>>,
+    transpose: bool,
+}
+
+impl InputOptions {
+    /// Splits the input options from the additional data.
+    ///
+    /// Returns the input options and the remaining additional data for the transformers.
+    pub fn extract(
+        additional_data: &Option<String>,
+    ) -> Result<(InputOptions, Option<String>), ParseTableError> {
+        let mut input_options = InputOptions::default();
+        let mut remaining = Vec::new();
+        for (key, value) in util::additional_options(additional_data) {
+            let invalid = || ParseTableError::invalid_option(key, value);
+            match key {
+                "skip" => input_options.skip = value.parse().map_err(|_| invalid())?,
+                "drop" => input_options.drop = value.parse().map_err(|_| invalid())?,
+                "rows" => input_options.rows = Some(value.parse().map_err(|_| invalid())?),
+                "cols" => {
+                    let columns: Result<Vec<AnyRange<usize>>, _> =
+                        value.split(',').map(|c| c.trim().parse()).collect();
+                    input_options.columns = Some(columns.map_err(|_| invalid())?);
+                }
+                "transpose" => input_options.transpose = value.parse().map_err(|_| invalid())?,
+                "" if value == "transpose" => input_options.transpose = true,
+                "" => remaining.push(value.to_string()),
+                key => remaining.push(format!("{key}={value}")),
+            }
+        }
+
+        let remaining = match remaining.is_empty() {
+            true => None,
+            false => Some(remaining.join(":")),
+        };
+        Ok((input_options, remaining))
+    }
+
+    /// Slices the lines of the raw content of an input file.
+    pub fn slice<'r>(&self, raw: &'r str) -> Cow<'r, str> {
+        if self.skip == 0 && self.drop == 0 && self.rows.is_none() {
+            return Cow::Borrowed(raw);
+        }
+
+        let mut lines: Vec<&str> = raw.lines().skip(self.skip).collect();
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+        lines.truncate(lines.len().saturating_sub(self.drop));
+        if let Some(rows) = &self.rows {
+            lines = rows.indices(lines.len()).map(|i| lines[i]).collect();
+        }
+
+        Cow::Owned(lines.join("\n"))
+    }
+
+    /// Applies the options to a table read by a transformer.
+    pub fn apply(&self, table: &mut Table) {
+        if let Some(columns) = &self.columns {
+            table.select_columns(columns);
+        }
+        if self.transpose {
+            table.transpose();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::InputOptions;
+
+    #[test]
+    fn slice_works() {
+        let raw = "device: x\ndate: y\nt\tU\n0\t1\n1\t2\n2\t3\nmean: 2\n\n";
+        let options = Some(String::from("skip=2:drop=1:rows=1..:;"));
+        let (input_options, remaining) = InputOptions::extract(&options).unwrap();
+        assert_eq!(remaining.as_deref(), Some(";"));
+        assert_eq!(input_options.slice(raw), "0\t1\n1\t2\n2\t3");
+
+        assert!(InputOptions::extract(&Some(String::from("skip=-1"))).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::cli::JoinType;
+use crate::table::cell::Cell;
+use crate::table::group::GroupKey;
+use crate::table::Table;
+use std::collections::{HashMap, VecDeque};
+
+impl Table {
+    /// Joins another table to the right side of the current table by matching key cells.
+    ///
+    /// Rows are matched if their cells in the `key` column are equal.
+    /// Blank key cells never match.
+    /// The key column of the other table is dropped as it would only repeat the key column of
+    /// this table.
+    /// Depending on the `join_type` rows without a match are either dropped or padded with blank
+    /// cells.
+    /// If `header` is set, the first rows of both tables are joined as header, even if the names
+    /// of their key columns differ.
+    /// The other table is consumed and becomes invalid after this operation.
+    pub fn join(&mut self, mut other: Self, key: usize, join_type: JoinType, header: bool) {
+        let keep_left = matches!(join_type, JoinType::Left | JoinType::Outer);
+        let keep_right = matches!(join_type, JoinType::Right | JoinType::Outer);
+
+        let other_width = match key < other.width {
+            true => other.width - 1,
+            false => other.width,
+        };
+        let without_key = |row: &Vec<Cell>| -> Vec<Cell> {
+            row.iter()
+                .enumerate()
+                .filter(|(i, _)| *i != key)
+                .map(|(_, c)| c.clone())
+                .collect()
+        };
+
+        let mut values = VecDeque::with_capacity(self.values.len());
+        if header {
+            let mut joined = match self.values.pop_front() {
+                Some(row) => row,
+                None => Self::create_blank_row(self.width),
+            };
+            let other_header = match other.values.pop_front() {
+                Some(row) => row,
+                None => Self::create_blank_row(other.width),
+            };
+            joined.append(&mut without_key(&other_header));
+            values.push_back(joined);
+        }
+
+        let mut other_indices: HashMap<GroupKey, Vec<usize>> = HashMap::new();
+        for (j, other_row) in other.values.iter().enumerate() {
+            match other_row.get(key) {
+                None | Some(Cell::Blank) => (),
+                Some(cell) => other_indices.entry(cell.into()).or_default().push(j),
+            }
+        }
+
+        let mut matched = vec![false; other.values.len()];
+        for row in self.values.iter() {
+            let indices = match row.get(key) {
+                None | Some(Cell::Blank) => None,
+                Some(cell) => other_indices.get(&cell.into()),
+            };
+            for &j in indices.into_iter().flatten() {
+                matched[j] = true;
+                let mut joined = row.clone();
+                joined.append(&mut without_key(&other.values[j]));
+                values.push_back(joined);
+            }
+
+            if indices.is_none() && keep_left {
+                let mut joined = row.clone();
+                joined.append(&mut Self::create_blank_row(other_width));
+                values.push_back(joined);
+            }
+        }
+
+        if keep_right {
+            for (other_row, _) in other.values.iter().zip(matched).filter(|(_, m)| !m) {
+                let mut joined = Self::create_blank_row(self.width);
+                if let (Some(cell), Some(key_cell)) = (joined.get_mut(key), other_row.get(key)) {
+                    *cell = key_cell.clone();
+                }
+                joined.append(&mut without_key(other_row));
+                values.push_back(joined);
+            }
+        }
+
+        let width = self.width + other_width;
+        *self = Table::from(values);
+        self.pad_right(width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::JoinType;
+    use crate::table::tests::{cells, table};
+
+    #[test]
+    fn join_types() {
+        let left = || table(&[&["1", "a"], &["2", "b"], &["3", "c"]]);
+        let right = || table(&[&["3", "z"], &["1.0", "x"], &["4", "w"]]);
+
+        let mut inner = left();
+        inner.join(right(), 0, JoinType::Inner, false);
+        assert_eq!(cells(&inner), [["1i", "a", "x"], ["3i", "c", "z"]]);
+
+        let mut left_join = left();
+        left_join.join(right(), 0, JoinType::Left, false);
+        assert_eq!(
+            cells(&left_join),
+            [["1i", "a", "x"], ["2i", "b", "[]"], ["3i", "c", "z"]]
+        );
+
+        let mut right_join = left();
+        right_join.join(right(), 0, JoinType::Right, false);
+        assert_eq!(
+            cells(&right_join),
+            [["1i", "a", "x"], ["3i", "c", "z"], ["4i", "[]", "w"]]
+        );
+
+        let mut outer = left();
+        outer.join(right(), 0, JoinType::Outer, false);
+        assert_eq!(outer.width, 3);
+        assert_eq!(
+            cells(&outer),
+            [
+                ["1i", "a", "x"],
+                ["2i", "b", "[]"],
+                ["3i", "c", "z"],
+                ["4i", "[]", "w"]
+            ]
+        );
+    }
+
+    #[test]
+    fn join_header() {
+        let left = table(&[&["id", "name"], &["1", "a"], &["2", "b"]]);
+        let right = table(&[&["ID", "value"], &["2", "y"], &["id", "z"]]);
+
+        let mut joined = left;
+        joined.join(right, 0, JoinType::Outer, true);
+        assert_eq!(
+            cells(&joined),
+            [
+                ["id", "name", "value"],
+                ["1i", "a", "[]"],
+                ["2i", "b", "y"],
+                ["id", "[]", "z"]
+            ]
+        );
+    }
+}
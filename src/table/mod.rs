@@ -1,13 +1,28 @@
-use crate::cli::{Args, DecimalSeparator};
+use crate::cli::{
+    Args, CsvQuoteStyle, DecimalSeparator, JsonLayout, LineTerminator, Notation, OutTypes,
+    UncertaintyStyle,
+};
 use crate::table::cell::Cell;
-use crate::util::AnyRange;
+use crate::util::Selector;
 use std::cmp;
 use std::collections::VecDeque;
 use std::fmt::{Debug, Formatter};
+use std::num::NonZeroU16;
 use tabled::builder::Builder;
 
 pub mod cell;
+mod compute;
+pub mod expr;
+mod filter;
+mod group;
+mod input;
+mod join;
+mod select;
+mod sort;
 mod transform;
+mod uncertainty;
+pub use input::InputOptions;
+pub use transform::detect::detect_type;
 pub use transform::ParseTableError;
 
 /// A general table structure that holds cells.
@@ -19,7 +34,7 @@ pub use transform::ParseTableError;
 /// The struct ensures that the height and width of the table are always consistent when
 /// concatenating or stacking tables.
 pub struct Table {
-    // TODO: in all implementations these are not used correctly but are 1 short
+    /// The number of rows, including a header row.
     height: usize,
     width: usize,
     values: VecDeque<Vec<Cell>>,
@@ -80,6 +95,70 @@ impl Table {
         self.height += other.height;
     }
 
+    /// Stacks another table on top of the current table, aligning the columns by their header.
+    ///
+    /// The first row of both tables is treated as the header holding the column names.
+    /// Columns of the other table are placed below the column of the same name, columns that
+    /// only exist in the other table are appended to the right.
+    /// Missing cells are padded with blank cells and only the header of the current table is
+    /// kept.
+    /// The other table is consumed and becomes invalid after this operation.
+    pub fn stack_by_header(&mut self, mut other: Self) {
+        let mut header = self.values.pop_front().unwrap_or_default();
+        let other_header = other.values.pop_front().unwrap_or_default();
+
+        // every column of the other table is mapped to a column of the current table,
+        // a column may only be used once per table to keep duplicate names apart
+        let mut used = vec![false; header.len()];
+        let mut mapping = Vec::with_capacity(other_header.len());
+        for name in other_header {
+            let position = header
+                .iter()
+                .zip(used.iter())
+                .position(|(h, used)| !used && *h == name);
+            let position = match position {
+                Some(position) => position,
+                None => {
+                    header.push(name);
+                    used.push(false);
+                    header.len() - 1
+                }
+            };
+            used[position] = true;
+            mapping.push(position);
+        }
+
+        let width = header.len();
+        self.pad_right(width);
+        self.values.push_front(header);
+        while let Some(row) = other.values.pop_front() {
+            let mut mapped_row = Self::create_blank_row(width);
+            for (cell, position) in row.into_iter().zip(mapping.iter()) {
+                mapped_row[*position] = cell;
+            }
+            self.values.push_back(mapped_row);
+        }
+        self.height = self.values.len();
+    }
+
+    /// Transposes the table, turning its rows into columns.
+    ///
+    /// Missing cells of ragged rows are filled with blank cells.
+    pub fn transpose(&mut self) {
+        let width = self.values.iter().map(Vec::len).max().unwrap_or(0);
+        let mut values: VecDeque<Vec<Cell>> = (0..width)
+            .map(|_| Vec::with_capacity(self.values.len()))
+            .collect();
+        for row in self.values.drain(..) {
+            let mut row = row.into_iter();
+            for column in values.iter_mut() {
+                column.push(row.next().unwrap_or_default());
+            }
+        }
+
+        *self = Table::from(values);
+    }
+
     /// Creates a new row of blank cells with the specified width.
     fn create_blank_row(width: usize) -> Vec<Cell> {
         let mut row = Vec::with_capacity(width);
@@ -104,12 +183,10 @@ impl Debug for Table {
 
 impl From<VecDeque<Vec<Cell>>> for Table {
     fn from(mut values: VecDeque<Vec<Cell>>) -> Self {
-        let (mut width, mut height) = (0, 0);
-        for (i, row) in values.iter().enumerate() {
-            height = i;
-            for (j, _) in row.iter().enumerate() {
-                width = cmp::max(width, j + 1)
-            }
+        let height = values.len();
+        let mut width = 0;
+        for row in values.iter() {
+            width = cmp::max(width, row.len());
         }
         for row in values.iter_mut() {
             row.resize(width, Cell::Blank);
@@ -124,27 +201,134 @@ impl From<VecDeque<Vec<Cell>>> for Table {
 
 #[derive(Debug, Default)]
 pub struct FormatOptions {
-    precision: Option<u16>,
-    exponent: bool,
-    decimal_sep: DecimalSeparator,
-    sign: bool,
+    precision: Vec<(Selector, u16)>,
+    significant: Vec<(Selector, NonZeroU16)>,
+    notation: Vec<(Selector, Notation)>,
+    decimal_sep: Vec<(Selector, DecimalSeparator)>,
+    sign: Vec<(Selector, bool)>,
+    uncertainty_style: UncertaintyStyle,
+    uncertainty_digits: Option<u8>,
+    latex: bool,
     hline: bool,
-    csv_sep: String,
-    prefix: Vec<(AnyRange<usize>, String)>,
-    suffix: Vec<(AnyRange<usize>, String)>,
+    csv_sep: u8,
+    csv_quote_style: CsvQuoteStyle,
+    csv_quote: u8,
+    csv_terminator: LineTerminator,
+    json_numbers: bool,
+    json_layout: JsonLayout,
+    m_var: String,
+    prefix: Vec<(Selector, String)>,
+    suffix: Vec<(Selector, String)>,
 }
 
 impl From<Args> for FormatOptions {
     fn from(value: Args) -> Self {
         FormatOptions {
             precision: value.precision,
-            exponent: value.exponent,
+            significant: value.significant,
+            notation: match (value.exponent, value.engineering, value.si) {
+                (true, _, _) => Some(Notation::Scientific),
+                (_, true, _) => Some(Notation::Engineering),
+                (_, _, true) => Some(Notation::Si),
+                _ => None,
+            }
+            .map(|notation| (Selector::ALL, notation))
+            .into_iter()
+            .chain(value.notation)
+            .collect(),
             decimal_sep: value.decimal_sep,
             sign: value.sign,
+            uncertainty_style: value.uncertainty_style,
+            uncertainty_digits: value.uncertainty_digits,
+            latex: value.to == OutTypes::Tex,
             hline: value.hline,
             csv_sep: value.csv_sep,
+            csv_quote_style: value.csv_quote_style,
+            csv_quote: value.csv_quote,
+            csv_terminator: value.csv_terminator,
+            json_numbers: value.json_numbers,
+            json_layout: value.json_layout,
+            m_var: value.m_var,
             prefix: value.prefix,
             suffix: value.suffix,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::table::cell::Cell;
+    use crate::table::Table;
+    use std::collections::VecDeque;
+    use std::str::FromStr;
+
+    /// Builds a table from raw cells, parsed like the cells of an input file.
+    pub(crate) fn table(raw: &[&[&str]]) -> Table {
+        let values: VecDeque<Vec<Cell>> = raw
+            .iter()
+            .map(|r| r.iter().map(|c| Cell::from_str(c).unwrap()).collect())
+            .collect();
+        Table::from(values)
+    }
+
+    /// Lists the debug representation of every cell, row by row.
+    pub(crate) fn cells(table: &Table) -> Vec<Vec<String>> {
+        table
+            .values
+            .iter()
+            .map(|r| r.iter().map(|c| format!("{c:?}")).collect())
+            .collect()
+    }
+
+    #[test]
+    fn stack_by_header_aligns_columns() {
+        let mut a = table(&[&["t", "u"], &["1", "2"]]);
+        let b = table(&[&["i", "t"], &["3", "4"], &["5", "6"]]);
+        a.stack_by_header(b);
+
+        assert_eq!(
+            cells(&a),
+            [
+                ["t", "u", "i"],
+                ["1i", "2i", "[]"],
+                ["4i", "[]", "3i"],
+                ["6i", "[]", "5i"]
+            ]
+        );
+        assert_eq!(a.width, 3);
+        assert_eq!(a.height, 4);
+    }
+
+    #[test]
+    fn height_counts_rows() {
+        let mut a = table(&[&["1"], &["2"]]);
+        a.stack(table(&[&["3"], &["4"]]));
+        assert_eq!(a.height, 4);
+
+        a.concat(table(&[&["5"], &["6"], &["7"], &["8"], &["9"]]));
+        assert_eq!(a.height, 5);
+        assert_eq!(
+            cells(&a),
+            [
+                ["1i", "5i"],
+                ["2i", "6i"],
+                ["3i", "7i"],
+                ["4i", "8i"],
+                ["[]", "9i"]
+            ]
+        );
+    }
+
+    #[test]
+    fn transpose_ragged() {
+        let mut table = table(&[&["1", "2", "3"], &["4"]]);
+        table.values[1].truncate(1);
+        table.transpose();
+
+        assert_eq!(cells(&table), [["1i", "4i"], ["2i", "[]"], ["3i", "[]"]]);
+        assert_eq!((table.width, table.height), (2, 3));
+
+        table.transpose();
+        assert_eq!((table.width, table.height), (3, 2));
+    }
+}
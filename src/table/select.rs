@@ -0,0 +1,42 @@
+use crate::table::Table;
+use crate::util::AnyRange;
+
+impl Table {
+    /// Keeps only the selected columns in the order of the given ranges.
+    ///
+    /// Columns may be selected multiple times, ranges beyond the width of the table select
+    /// nothing.
+    pub fn select_columns(&mut self, ranges: &[AnyRange<usize>]) {
+        let columns: Vec<usize> = ranges
+            .iter()
+            .flat_map(|range| range.indices(self.width))
+            .collect();
+
+        for row in self.values.iter_mut() {
+            *row = columns.iter().map(|i| row[*i].clone()).collect();
+        }
+        self.width = columns.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::tests::{cells, table};
+    use crate::util::AnyRange;
+
+    #[test]
+    fn select_columns_works() {
+        let mut table = table(&[
+            &["0", "1", "2", "3", "4", "5"],
+            &["10", "11", "12", "13", "14", "15"],
+        ]);
+        let ranges: Vec<AnyRange<usize>> = ["4..", "0", "1..=2", "9"]
+            .iter()
+            .map(|r| r.parse().unwrap())
+            .collect();
+        table.select_columns(&ranges);
+
+        assert_eq!(table.width, 5);
+        assert_eq!(cells(&table)[1], ["14i", "15i", "10i", "11i", "12i"]);
+    }
+}
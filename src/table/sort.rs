@@ -0,0 +1,136 @@
+use crate::cli::{BlankOrder, SortOrder};
+use crate::table::cell::Cell;
+use crate::table::Table;
+use std::cmp::Ordering;
+
+impl Table {
+    /// Sorts the rows of the table by the given key columns.
+    ///
+    /// Keys are compared in order, later keys are only used if all previous keys are equal.
+    /// Numerical cells are compared by value and sorted before string cells, which are compared in
+    /// natural order (e.g. 'a2' before 'a10').
+    /// Blank cells are placed first or last regardless of the sort order.
+    /// The sort is stable and, if `header` is set, the first row is kept at the top.
+    pub fn sort(&mut self, keys: &[(usize, SortOrder)], blanks: BlankOrder, header: bool) {
+        let header_row = match header {
+            true => self.values.pop_front(),
+            false => None,
+        };
+
+        self.values.make_contiguous().sort_by(|a, b| {
+            for (column, order) in keys {
+                let a = a.get(*column).unwrap_or(&Cell::Blank);
+                let b = b.get(*column).unwrap_or(&Cell::Blank);
+                let ordering =
+                    match (a, b, blanks) {
+                        (Cell::Blank, Cell::Blank, _) => Ordering::Equal,
+                        (Cell::Blank, _, BlankOrder::First)
+                        | (_, Cell::Blank, BlankOrder::Last) => Ordering::Less,
+                        (Cell::Blank, _, BlankOrder::Last)
+                        | (_, Cell::Blank, BlankOrder::First) => Ordering::Greater,
+                        (a, b, _) => match order {
+                            SortOrder::Asc => compare_cells(a, b),
+                            SortOrder::Desc => compare_cells(b, a),
+                        },
+                    };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            Ordering::Equal
+        });
+
+        if let Some(header_row) = header_row {
+            self.values.push_front(header_row);
+        }
+    }
+}
+
+/// Compares two non-blank cells, numbers are sorted before strings.
+fn compare_cells(a: &Cell, b: &Cell) -> Ordering {
+    match (a.as_f64(), b.as_f64(), a, b) {
+        (Some(a), Some(b), _, _) => a.total_cmp(&b),
+        (Some(_), None, _, _) => Ordering::Less,
+        (None, Some(_), _, _) => Ordering::Greater,
+        (None, None, Cell::Str(a), Cell::Str(b)) => natural_cmp(a, b),
+        _ => Ordering::Equal,
+    }
+}
+
+/// Compares two strings in natural order, comparing runs of digits by their value.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x: String = std::iter::from_fn(|| a.next_if(char::is_ascii_digit)).collect();
+                let y: String = std::iter::from_fn(|| b.next_if(char::is_ascii_digit)).collect();
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let ordering = x.len().cmp(&y.len()).then_with(|| x.cmp(y));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.cmp(&y);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{BlankOrder, SortOrder};
+    use crate::table::tests::{cells, table};
+
+    #[test]
+    fn sort_works() {
+        let mut table = table(&[
+            &["name", "value"],
+            &["a10", "1"],
+            &["a2", ""],
+            &["b", "2.5"],
+            &["a2", "3"],
+            &["", "1"],
+        ]);
+
+        table.sort(
+            &[(1, SortOrder::Desc), (0, SortOrder::Asc)],
+            BlankOrder::Last,
+            true,
+        );
+        assert_eq!(
+            cells(&table),
+            [
+                ["name", "value"],
+                ["a2", "3i"],
+                ["b", "2.5f"],
+                ["a10", "1i"],
+                ["[]", "1i"],
+                ["a2", "[]"]
+            ]
+        );
+
+        table.sort(&[(0, SortOrder::Asc)], BlankOrder::First, true);
+        assert_eq!(
+            cells(&table),
+            [
+                ["name", "value"],
+                ["[]", "1i"],
+                ["a2", "3i"],
+                ["a2", "[]"],
+                ["a10", "1i"],
+                ["b", "2.5f"]
+            ]
+        );
+    }
+}
@@ -1,21 +1,30 @@
+use crate::cli::{CsvQuoteStyle, LineTerminator};
 use crate::table::cell::Cell;
+use crate::table::transform::detect::{sniff_separator, Separator};
 use crate::table::transform::ParseTableError;
 use crate::table::FormatOptions;
 use crate::table::Table;
-use csv::ReaderBuilder;
+use crate::util;
+use crate::util::parse_char;
+use csv::{QuoteStyle, ReaderBuilder, Terminator, Trim, WriterBuilder};
 use std::collections::VecDeque;
 use std::str::FromStr;
 
 impl Table {
     /// Construct a table from the contents of a csv file.
-    pub fn from_csv(raw: &str, additional_data: &Option<String>) -> Result<Table, ParseTableError> {
-        // determine delimiter
-        let delimiter = additional_data.clone().and_then(|s| s.chars().next()).unwrap_or(';');
+    ///
+    /// The additional data may set the delimiter directly (e.g. 'example.csv:|', or
+    /// 'example.csv::' for a colon) and configure the dialect with options, see [`CsvDialect`].
+    /// Without a delimiter or dialect, the delimiter is sniffed from the content if `sniff` is
+    /// set, e.g. for files without a known extension.
+    pub fn from_csv(
+        raw: &str,
+        additional_data: &Option<String>,
+        sniff: bool,
+    ) -> Result<Table, ParseTableError> {
+        let dialect = CsvDialect::from_additional_data(additional_data, sniff)?;
 
-        let mut reader = ReaderBuilder::new()
-            .has_headers(false)
-            .delimiter(delimiter as u8)
-            .from_reader(raw.as_bytes());
+        let mut reader = dialect.reader_builder(raw).from_reader(raw.as_bytes());
         let table: Result<VecDeque<Vec<Cell>>, ParseCsvTableError> = reader
             .records()
             .map(|r| {
@@ -30,19 +39,162 @@ impl Table {
     }
 
     /// Construct a csv representation.
+    ///
+    /// Cells are quoted according to RFC 4180 depending on the quote style, quotes inside of
+    /// quoted cells are escaped by doubling them.
     pub fn to_csv(&self, format_options: &FormatOptions) -> String {
-        let delimiter = format_options.csv_sep.as_str();
-        itertools::join(
-            self.values.iter().map(|row| {
-                itertools::join(
-                    row.iter()
-                        .enumerate()
-                        .map(|(i, c)| c.fmt(format_options, i)),
-                    delimiter,
-                )
-            }),
-            "\n",
-        )
+        let (terminator, last) = match format_options.csv_terminator {
+            LineTerminator::Lf => (Terminator::Any(b'\n'), "\n"),
+            LineTerminator::Crlf => (Terminator::CRLF, "\r\n"),
+        };
+        let quote_style = match format_options.csv_quote_style {
+            CsvQuoteStyle::Minimal => QuoteStyle::Necessary,
+            CsvQuoteStyle::All => QuoteStyle::Always,
+            CsvQuoteStyle::NonNumeric => QuoteStyle::NonNumeric,
+        };
+
+        let mut writer = WriterBuilder::new()
+            .delimiter(format_options.csv_sep)
+            .quote(format_options.csv_quote)
+            .quote_style(quote_style)
+            .terminator(terminator)
+            .from_writer(Vec::new());
+        for (r, row) in self.values.iter().enumerate() {
+            let record = row
+                .iter()
+                .enumerate()
+                .map(|(i, c)| c.fmt(format_options, r, i));
+            writer
+                .write_record(record)
+                .expect("rows have the same width");
+        }
+        let csv = writer.into_inner().expect("writing to memory never fails");
+        let csv = String::from_utf8(csv).expect("cells are valid utf-8");
+
+        // like all other output types, the last row is not terminated
+        match csv.strip_suffix(last) {
+            Some(csv) => csv.to_string(),
+            None => csv,
+        }
+    }
+}
+
+/// The dialect used to read a csv file.
+///
+/// The dialect is configured by the options of the additional data:
+/// - `<char>` or `sep=<char>`: delimiter, defaults to ';', `auto` sniffs it from the content,
+///   falling back to ';'
+/// - `quote=<char>`: quote character, defaults to '"', `none` disables quoting
+/// - `escape=<char>`: escape character for quotes, defaults to `double` for doubled quotes
+/// - `comment=<char>`: lines starting with this character are skipped
+/// - `trim=<none|headers|fields|all>`: trims whitespace around cells
+/// - `flexible=<true|false>`: allows records of different lengths
+/// - `dialect=<excel|excel-de|unix>`: presets that are applied before all other options
+///
+/// Characters may also be given by name, e.g. `tab`, `space`, `colon` or `backslash`.
+#[derive(Debug, Clone)]
+pub struct CsvDialect {
+    delimiter: Option<u8>,
+    quote: Option<u8>,
+    escape: Option<u8>,
+    comment: Option<u8>,
+    trim: Trim,
+    flexible: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        CsvDialect {
+            delimiter: Some(b';'),
+            quote: Some(b'"'),
+            escape: None,
+            comment: None,
+            trim: Trim::None,
+            flexible: false,
+        }
+    }
+}
+
+impl CsvDialect {
+    /// Parses the dialect from the additional data of an input file.
+    ///
+    /// Without a dialect preset, the delimiter is sniffed if `sniff` is set.
+    pub fn from_additional_data(
+        additional_data: &Option<String>,
+        sniff: bool,
+    ) -> Result<Self, ParseTableError> {
+        let options = util::additional_options(additional_data);
+
+        let mut dialect = match options.iter().find(|(key, _)| *key == "dialect") {
+            None if sniff => CsvDialect {
+                delimiter: None,
+                ..Default::default()
+            },
+            None => CsvDialect::default(),
+            Some((_, "excel")) => CsvDialect {
+                delimiter: Some(b','),
+                ..Default::default()
+            },
+            Some((_, "excel-de")) => CsvDialect {
+                delimiter: Some(b';'),
+                ..Default::default()
+            },
+            Some((_, "unix")) => CsvDialect {
+                delimiter: Some(b','),
+                escape: Some(b'\\'),
+                ..Default::default()
+            },
+            Some((key, value)) => return Err(ParseTableError::invalid_option(key, value)),
+        };
+
+        for (key, value) in options {
+            let invalid = || ParseTableError::invalid_option(key, value);
+            match (key, value) {
+                ("dialect", _) => (),
+                ("" | "sep", "auto") => dialect.delimiter = None,
+                ("" | "sep", value) => {
+                    dialect.delimiter = Some(parse_char(value).ok_or_else(invalid)?)
+                }
+                ("quote", "none") => dialect.quote = None,
+                ("quote", value) => dialect.quote = Some(parse_char(value).ok_or_else(invalid)?),
+                ("escape", "double") => dialect.escape = None,
+                ("escape", value) => dialect.escape = Some(parse_char(value).ok_or_else(invalid)?),
+                ("comment", value) => {
+                    dialect.comment = Some(parse_char(value).ok_or_else(invalid)?)
+                }
+                ("trim", "none") => dialect.trim = Trim::None,
+                ("trim", "headers") => dialect.trim = Trim::Headers,
+                ("trim", "fields") => dialect.trim = Trim::Fields,
+                ("trim", "all") => dialect.trim = Trim::All,
+                ("flexible", value) => dialect.flexible = value.parse().map_err(|_| invalid())?,
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(dialect)
+    }
+
+    /// Creates a reader builder for this dialect, sniffing the delimiter from `raw` if not set.
+    fn reader_builder(&self, raw: &str) -> ReaderBuilder {
+        let delimiter = self
+            .delimiter
+            .unwrap_or_else(|| match sniff_separator(raw) {
+                Some(Separator::Char(c)) => u8::try_from(c).unwrap_or(b';'),
+                _ => b';',
+            });
+
+        let mut builder = ReaderBuilder::new();
+        builder
+            .has_headers(false)
+            .delimiter(delimiter)
+            .quoting(self.quote.is_some())
+            .quote(self.quote.unwrap_or(b'"'))
+            .double_quote(self.escape.is_none())
+            .escape(self.escape)
+            .comment(self.comment)
+            .trim(self.trim)
+            .flexible(self.flexible);
+        builder
     }
 }
 
@@ -53,3 +205,78 @@ impl From<ParseCsvTableError> for ParseTableError {
         ParseTableError::Csv(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{CsvQuoteStyle, LineTerminator};
+    use crate::table::tests::cells;
+    use crate::table::{FormatOptions, Table};
+
+    #[test]
+    fn from_csv_dialects() {
+        let raw = "# comment\n a ;'b;c'\n1,5;'it''s'";
+        let table = Table::from_csv(
+            raw,
+            &Some(String::from("quote=':comment=#:trim=all")),
+            false,
+        )
+        .unwrap();
+        assert_eq!(cells(&table)[0], ["a", "b;c"]);
+        assert_eq!(cells(&table)[1], ["1.5f", "it's"]);
+
+        let raw = "\"a\\\"b\",1\n2";
+        let table = Table::from_csv(
+            raw,
+            &Some(String::from("dialect=unix:flexible=true")),
+            false,
+        );
+        assert_eq!(cells(&table.unwrap())[0], ["a\"b", "1i"]);
+
+        let table = Table::from_csv(raw, &Some(String::from("dialect=excel")), false);
+        assert!(table.is_err());
+
+        let table = Table::from_csv("a:b\n1:2", &Some(String::from(":")), false).unwrap();
+        assert_eq!(cells(&table), [["a", "b"], ["1i", "2i"]]);
+
+        let table = Table::from_csv(raw, &Some(String::from("trim=sometimes")), false);
+        assert!(table.is_err());
+
+        let raw = "a,b\n1,5";
+        let table = Table::from_csv(raw, &None, false).unwrap();
+        assert_eq!(cells(&table)[0], ["a,b"]);
+        let table = Table::from_csv(raw, &Some(String::from("auto")), false).unwrap();
+        assert_eq!(cells(&table)[0], ["a", "b"]);
+        let table = Table::from_csv(raw, &None, true).unwrap();
+        assert_eq!(cells(&table)[0], ["a", "b"]);
+        let table = Table::from_csv(raw, &Some(String::from("dialect=excel-de")), true).unwrap();
+        assert_eq!(cells(&table)[0], ["a,b"]);
+    }
+
+    #[test]
+    fn to_csv_quotes() {
+        let table = Table::from_dat("a,b\t\"c\"\t1,5\n\t2", &None, false).unwrap();
+        let mut format_options = FormatOptions {
+            csv_sep: b',',
+            csv_quote: b'"',
+            ..Default::default()
+        };
+        assert_eq!(
+            table.to_csv(&format_options),
+            "\"a,b\",\"\"\"c\"\"\",1.5\n2,,"
+        );
+
+        format_options.csv_quote_style = CsvQuoteStyle::NonNumeric;
+        format_options.csv_terminator = LineTerminator::Crlf;
+        assert_eq!(
+            table.to_csv(&format_options),
+            "\"a,b\",\"\"\"c\"\"\",1.5\r\n2,\"\",\"\""
+        );
+
+        format_options.csv_quote_style = CsvQuoteStyle::All;
+        format_options.csv_quote = b'\'';
+        assert_eq!(
+            table.to_csv(&format_options),
+            "'a,b','\"c\"','1.5'\r\n'2','',''"
+        );
+    }
+}
@@ -1,18 +1,73 @@
 use crate::table::cell::Cell;
+use crate::table::transform::detect::{sniff_separator, Separator};
 use crate::table::transform::ParseTableError;
 use crate::table::FormatOptions;
 use crate::table::Table;
+use crate::util;
 use std::collections::VecDeque;
 use std::str::FromStr;
 
 impl Table {
     /// Construct a table from the contents of a dat file.
-    pub fn from_dat(raw: &str, _: &Option<String>) -> Result<Table, ParseTableError> {
+    ///
+    /// The additional data may set the separator directly (e.g. 'example.dat:ws') or with the
+    /// `sep` option, using `ws` to split at runs of whitespace and `auto` to sniff it from the
+    /// content, falling back to tabs.
+    /// If not set, the separator is sniffed if `sniff` is set, e.g. for files without a known
+    /// extension, and a tab otherwise.
+    ///
+    /// With the `fixed` option, the lines are split at fixed positions instead.
+    /// The positions may be given as comma separated list of column starts (e.g. `fixed=8,16`),
+    /// otherwise every word of the first non-empty line starts a new column.
+    pub fn from_dat(
+        raw: &str,
+        additional_data: &Option<String>,
+        sniff: bool,
+    ) -> Result<Table, ParseTableError> {
+        let mut separator = match sniff {
+            true => None,
+            false => Some(Separator::Char('\t')),
+        };
+        let mut fixed = None;
+        for (key, value) in util::additional_options(additional_data) {
+            let invalid = || ParseTableError::invalid_option(key, value);
+            match (key, value) {
+                ("" | "sep", "ws" | "whitespace") => separator = Some(Separator::Whitespace),
+                ("" | "sep", "tab") => separator = Some(Separator::Char('\t')),
+                ("" | "sep", "auto") => separator = None,
+                ("" | "sep", value) if value.chars().count() == 1 => {
+                    separator = value.chars().next().map(Separator::Char)
+                }
+                ("", "fixed") => fixed = Some(Vec::new()),
+                ("fixed", value) => {
+                    let positions: Result<Vec<usize>, _> =
+                        value.split(',').map(|p| p.trim().parse()).collect();
+                    fixed = Some(positions.map_err(|_| invalid())?);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        if let Some(mut positions) = fixed {
+            if positions.is_empty() {
+                positions = header_positions(raw);
+            }
+            return Ok(Table::from_fixed_width(raw, positions));
+        }
+
+        let separator = separator
+            .or_else(|| sniff_separator(raw))
+            .unwrap_or(Separator::Char('\t'));
         let table: VecDeque<Vec<Cell>> = raw
             .lines()
             .map(|l| {
-                l.trim()
-                    .split('\t')
+                let l = l.trim();
+                let cells: Vec<&str> = match separator {
+                    Separator::Char(c) => l.split(c).collect(),
+                    Separator::Whitespace => l.split_whitespace().collect(),
+                };
+                cells
+                    .into_iter()
                     .map(|c| Cell::from_str(c).expect("infallible"))
                     .collect()
             })
@@ -20,13 +75,119 @@ impl Table {
         Ok(table.into())
     }
 
+    /// Construct a table by splitting every line at the given character positions.
+    fn from_fixed_width(raw: &str, mut positions: Vec<usize>) -> Table {
+        positions.retain(|p| *p != 0);
+        positions.sort_unstable();
+        positions.dedup();
+
+        let table: VecDeque<Vec<Cell>> = raw
+            .lines()
+            .map(|l| {
+                let chars: Vec<char> = l.chars().collect();
+                let starts = std::iter::once(0).chain(positions.iter().copied());
+                let ends = positions.iter().copied().chain(std::iter::once(usize::MAX));
+                starts
+                    .zip(ends)
+                    .map(|(start, end)| {
+                        let start = start.min(chars.len());
+                        let end = end.min(chars.len());
+                        let cell: String = chars[start..end].iter().collect();
+                        Cell::from_str(cell.trim()).expect("infallible")
+                    })
+                    .collect()
+            })
+            .collect();
+        table.into()
+    }
+
     /// Construct a dat representation.
     pub fn to_dat(&self, format_options: &FormatOptions) -> String {
         let mut output = String::new();
-        for (i, row) in self.values.iter().enumerate() {
-            output +=
-                &*(itertools::join(row.iter().map(|c| c.fmt(format_options, i)), "\t") + "\n");
+        for (r, row) in self.values.iter().enumerate() {
+            let items = row
+                .iter()
+                .enumerate()
+                .map(|(i, c)| c.fmt(format_options, r, i));
+            output += &*(itertools::join(items, "\t") + "\n");
         }
         output
     }
 }
+
+/// Determines the column starts of a fixed width table from the words of its first non-empty
+/// line.
+fn header_positions(raw: &str) -> Vec<usize> {
+    let header = raw.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let mut positions = Vec::new();
+    let mut previous_whitespace = true;
+    for (i, c) in header.chars().enumerate() {
+        if previous_whitespace && !c.is_whitespace() {
+            positions.push(i);
+        }
+        previous_whitespace = c.is_whitespace();
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::tests::cells;
+    use crate::table::{FormatOptions, Table};
+
+    #[test]
+    fn from_dat_layouts() {
+        let raw = "time   U [V]\n 0.5    1.25\n 1      -2\n";
+
+        let table = Table::from_dat(raw, &Some(String::from("ws")), false).unwrap();
+        assert_eq!(cells(&table)[0], ["time", "U", "[V]"]);
+        assert_eq!(cells(&table)[1], ["0.5f", "1.25f", "[]"]);
+
+        let table = Table::from_dat(raw, &Some(String::from("fixed=7")), false).unwrap();
+        assert_eq!(cells(&table)[0], ["time", "U [V]"]);
+        assert_eq!(cells(&table)[2], ["1i", "-2i"]);
+
+        let raw = "time  U\n0.5   1.25\n1     -2\n";
+        let table = Table::from_dat(raw, &Some(String::from("fixed")), false).unwrap();
+        assert_eq!(cells(&table)[1], ["0.5f", "1.25f"]);
+        assert_eq!(cells(&table)[2], ["1i", "-2i"]);
+
+        assert!(Table::from_dat(raw, &Some(String::from("fixed=a")), false).is_err());
+    }
+
+    #[test]
+    fn from_dat_decimal_comma() {
+        let raw = "1,5\n2,5\n3,25\n";
+        for (additional_data, sniff) in [
+            (None, false),
+            (Some(String::from("auto")), false),
+            (None, true),
+        ] {
+            let table = Table::from_dat(raw, &additional_data, sniff).unwrap();
+            assert_eq!(table.width, 1);
+            assert_eq!(cells(&table)[2], ["3.25f"]);
+        }
+
+        let table = Table::from_dat("a b\tc\n1\t2", &None, true).unwrap();
+        assert_eq!(cells(&table)[0], ["a b", "c"]);
+        let table = Table::from_dat("a b\n1 2", &None, true).unwrap();
+        assert_eq!(cells(&table)[0], ["a", "b"]);
+        let table = Table::from_dat("a b\n1 2", &Some(String::from("tab")), true).unwrap();
+        assert_eq!(cells(&table)[0], ["a b"]);
+    }
+
+    #[test]
+    fn to_dat_selects_like_csv() {
+        let table = Table::from_dat("1.5\t2.5\n3.5\t4.5", &None, false).unwrap();
+        let format_options = FormatOptions {
+            precision: vec![("c1".parse().unwrap(), 2), ("r1c0".parse().unwrap(), 0)],
+            csv_sep: b'\t',
+            ..Default::default()
+        };
+        assert_eq!(table.to_dat(&format_options), "1.5\t2.50\n4\t4.50\n");
+        assert_eq!(
+            table.to_csv(&format_options) + "\n",
+            table.to_dat(&format_options)
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use crate::cli::InTypes;
+use crate::table::transform::md;
+use crate::table::Table;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// The amount of lines inspected when sniffing the separator.
+const SNIFF_LINES: usize = 50;
+
+/// The separator between the cells of a delimited text file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    Char(char),
+    Whitespace,
+}
+
+/// Guesses the input type by looking at the content of a file.
+///
+/// Returns `None` if the content is empty, or starts with a bracket but is neither json nor a
+/// Matlab literal.
+/// Content that does not look like any structured type is considered delimited text, which is
+/// read as 'csv' if it is separated by ';', ',' or '|' and as 'dat' otherwise.
+pub fn detect_type(raw: &str) -> Option<InTypes> {
+    lazy_static! {
+        static ref TEX: Regex = Regex::new(r"\\begin\{(?:tabular\*?|tabularx|longtable)\}")
+            .expect("should be valid regex");
+        static ref M: Regex = Regex::new(r"(?m)^\s*[A-Za-z]\w*\s*=\s*(?:\.\.\.[^\n]*\n\s*)?[\[{]")
+            .expect("should be valid regex");
+    }
+
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    if trimmed.starts_with(['[', '{']) {
+        if serde_json::from_str::<serde_json::Value>(trimmed).is_ok() {
+            return Some(InTypes::Json);
+        }
+        let is_ndjson = trimmed
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .all(|l| serde_json::from_str::<serde_json::Value>(l).is_ok());
+        if is_ndjson {
+            return Some(InTypes::Ndjson);
+        }
+        return match M.is_match(raw) || Table::from_m(raw, &None).is_ok() {
+            true => Some(InTypes::M),
+            false => None,
+        };
+    }
+
+    if TEX.is_match(raw) {
+        return Some(InTypes::Tex);
+    }
+
+    if M.is_match(raw) {
+        return Some(InTypes::M);
+    }
+
+    let lines: Vec<&str> = raw.lines().collect();
+    if lines
+        .windows(2)
+        .any(|w| md::is_table_row(w[0]) && md::is_alignment_row(w[1]))
+    {
+        return Some(InTypes::Md);
+    }
+
+    match sniff_separator(raw) {
+        Some(Separator::Char(';' | ',' | '|')) => Some(InTypes::Csv),
+        _ => Some(InTypes::Dat),
+    }
+}
+
+/// Guesses the separator of delimited text.
+///
+/// A candidate is accepted if most of the inspected lines contain it equally often, candidates
+/// are checked in the order tab, ';', ',', '|' and runs of whitespace.
+/// Characters enclosed in '"' are not counted.
+/// ',' is never accepted if every field looks like a number with a decimal comma.
+pub fn sniff_separator(raw: &str) -> Option<Separator> {
+    lazy_static! {
+        static ref DECIMAL_COMMA: Regex =
+            Regex::new(r"^[+-]?\d+,\d+$").expect("should be valid regex");
+    }
+
+    let lines: Vec<&str> = raw
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .take(SNIFF_LINES)
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    let decimal_comma = lines
+        .iter()
+        .flat_map(|l| l.split_whitespace())
+        .all(|field| DECIMAL_COMMA.is_match(field));
+
+    for candidate in ['\t', ';', ',', '|'] {
+        if candidate == ',' && decimal_comma {
+            continue;
+        }
+        let counts = lines.iter().map(|l| count_unquoted(l, candidate));
+        if is_consistent(counts, lines.len()) {
+            return Some(Separator::Char(candidate));
+        }
+    }
+
+    let counts = lines.iter().map(|l| l.split_whitespace().count() - 1);
+    if is_consistent(counts, lines.len()) {
+        return Some(Separator::Whitespace);
+    }
+
+    None
+}
+
+/// Counts the occurrences of a character outside of '"' quotes.
+fn count_unquoted(line: &str, candidate: char) -> usize {
+    let mut quoted = false;
+    let mut count = 0;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c == candidate && !quoted => count += 1,
+            _ => (),
+        }
+    }
+    count
+}
+
+/// Checks if the most common non-zero count appears in at least 80% of the lines.
+fn is_consistent(counts: impl Iterator<Item = usize>, lines: usize) -> bool {
+    let mut frequencies: Vec<(usize, usize)> = Vec::new();
+    for count in counts.filter(|c| *c > 0) {
+        match frequencies.iter_mut().find(|(c, _)| *c == count) {
+            Some((_, frequency)) => *frequency += 1,
+            None => frequencies.push((count, 1)),
+        }
+    }
+
+    let most_common = frequencies.iter().map(|(_, f)| *f).max().unwrap_or(0);
+    most_common * 5 >= lines * 4
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::InTypes;
+    use crate::table::transform::detect::{detect_type, sniff_separator, Separator};
+
+    const EXAMPLE_CSV: &str = include_str!("../../../examples/example.csv");
+    const EXAMPLE_TXT: &str = include_str!("../../../examples/example.txt");
+    const EXAMPLE_M: &str = include_str!("../../../examples/example.m");
+
+    #[test]
+    fn detect_works() {
+        assert_eq!(detect_type(EXAMPLE_CSV), Some(InTypes::Csv));
+        assert_eq!(detect_type(EXAMPLE_TXT), Some(InTypes::Dat));
+        assert_eq!(detect_type(EXAMPLE_M), Some(InTypes::M));
+        assert_eq!(detect_type("[[1, 2], [3, 4]]"), Some(InTypes::Json));
+        assert_eq!(
+            detect_type("{\"a\": 1}\n{\"a\": 2}\n"),
+            Some(InTypes::Ndjson)
+        );
+        assert_eq!(
+            detect_type("| a | b |\n|---|---|\n| 1 | 2 |"),
+            Some(InTypes::Md)
+        );
+        assert_eq!(
+            detect_type("\\begin{tabular}{cc}\n1 & 2\n\\end{tabular}"),
+            Some(InTypes::Tex)
+        );
+        assert_eq!(detect_type("  \n"), None);
+        assert_eq!(detect_type("[1 2; 3 4]"), Some(InTypes::M));
+        assert_eq!(detect_type("[section]\nkey = value"), None);
+        assert_eq!(detect_type("{not: json}"), None);
+    }
+
+    #[test]
+    fn sniff_works() {
+        assert_eq!(sniff_separator(EXAMPLE_CSV), Some(Separator::Char(';')));
+        assert_eq!(sniff_separator(EXAMPLE_TXT), Some(Separator::Char('\t')));
+        assert_eq!(
+            sniff_separator("a,\"b,c\",d\n1,2,3"),
+            Some(Separator::Char(','))
+        );
+        assert_eq!(
+            sniff_separator("  1.0   2.0  3\n  4.5   5.0  6"),
+            Some(Separator::Whitespace)
+        );
+        assert_eq!(sniff_separator("1\n2\n3"), None);
+        assert_eq!(sniff_separator("1,5\n2,5\n3,25"), None);
+        assert_eq!(
+            sniff_separator("1,5 2,5\n3,25 -4,0"),
+            Some(Separator::Whitespace)
+        );
+        assert_eq!(detect_type("1,5\n2,5\n3,25"), Some(InTypes::Dat));
+    }
+}
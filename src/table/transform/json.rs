@@ -1,36 +1,209 @@
+use crate::cli::JsonLayout;
 use crate::table::cell::Cell;
 use crate::table::transform::ParseTableError;
 use crate::table::{FormatOptions, Table};
-use serde_json::Error;
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Error, Number, Value};
 use std::collections::VecDeque;
+use std::fmt::Formatter;
 use std::str::FromStr;
 
 impl Table {
+    /// Construct a table from the contents of a json file.
+    ///
+    /// The file may either contain an array of rows, each being an array of values, or an array of
+    /// records, each being an object.
+    /// For records, the keys are collected in order of appearance and written as header row.
+    /// There are no boolean cells, so booleans are read as the strings 'true' and 'false'.
     pub fn from_json(raw: &str, _: &Option<String>) -> Result<Table, ParseTableError> {
-        let parsed_table: VecDeque<Vec<String>> = serde_json::from_str(raw)?;
-        let table: VecDeque<Vec<Cell>> = parsed_table
-            .iter()
-            .map(|r| {
-                r.iter()
-                    .map(|c| Cell::from_str(c).expect("infallible"))
-                    .collect()
-            })
-            .collect();
-        Ok(table.into())
+        let parsed_rows: Vec<JsonRow> = serde_json::from_str(raw)?;
+        Ok(Table::from_json_rows(parsed_rows))
     }
 
+    /// Construct a table from parsed json rows, collecting the keys of all records as header.
+    pub(super) fn from_json_rows(parsed_rows: Vec<JsonRow>) -> Table {
+        let mut keys: Vec<String> = Vec::new();
+        for row in parsed_rows.iter() {
+            if let JsonRow::Record(record) = row {
+                for (key, _) in record {
+                    if !keys.contains(key) {
+                        keys.push(key.clone());
+                    }
+                }
+            }
+        }
+
+        let mut table: VecDeque<Vec<Cell>> = VecDeque::with_capacity(parsed_rows.len() + 1);
+        if !keys.is_empty() {
+            table.push_back(keys.iter().map(|k| Cell::Str(k.clone())).collect());
+        }
+        for row in parsed_rows {
+            let row = match row {
+                JsonRow::Array(values) => values.into_iter().map(value_to_cell).collect(),
+                JsonRow::Record(mut record) => keys
+                    .iter()
+                    .map(|key| match record.iter().position(|(k, _)| k == key) {
+                        Some(i) => value_to_cell(record.swap_remove(i).1),
+                        None => Cell::Blank,
+                    })
+                    .collect(),
+            };
+            table.push_back(row);
+        }
+        table.into()
+    }
+
+    /// Construct a json representation.
+    ///
+    /// Depending on the layout, every row is either written as an array or, using the first row
+    /// as keys, as an object.
     pub fn to_json(&self, format_options: &FormatOptions) -> String {
-        let mut str_table: Vec<Vec<String>> = Vec::with_capacity(self.height);
+        // only strings and numbers, should be ok
+        match format_options.json_layout {
+            JsonLayout::Rows => {
+                serde_json::to_string_pretty(&self.to_json_values(format_options)).unwrap()
+            }
+            JsonLayout::Records => {
+                serde_json::to_string_pretty(&self.to_json_records(format_options)).unwrap()
+            }
+        }
+    }
+
+    /// Converts every cell into a json value.
+    fn to_json_values(&self, format_options: &FormatOptions) -> Vec<Vec<Value>> {
+        let mut value_table: Vec<Vec<Value>> = Vec::with_capacity(self.height);
 
-        for row in self.values.iter() {
-            let mut str_row = Vec::with_capacity(self.width);
+        for (r, row) in self.values.iter().enumerate() {
+            let mut value_row = Vec::with_capacity(self.width);
             for (i, cell) in row.iter().enumerate() {
-                str_row.push(cell.fmt(format_options, i));
+                value_row.push(cell_to_value(cell, format_options, r, i));
+            }
+            value_table.push(value_row);
+        }
+
+        value_table
+    }
+
+    /// Converts every row but the first into a record, using the first row as keys.
+    ///
+    /// Repeated keys get a numbered suffix, e.g. 'U', 'U_2', so that no value is lost.
+    pub(super) fn to_json_records(&self, format_options: &FormatOptions) -> Vec<JsonRecord> {
+        let mut rows = self.to_json_values(format_options).into_iter();
+        let mut keys: Vec<String> = Vec::with_capacity(self.width);
+        for key in rows.next().unwrap_or_default() {
+            let key = match key {
+                Value::String(s) => s,
+                k => k.to_string(),
+            };
+            let mut unique = key.clone();
+            for n in 2.. {
+                if !keys.contains(&unique) {
+                    break;
+                }
+                unique = format!("{key}_{n}");
             }
-            str_table.push(str_row);
+            keys.push(unique);
         }
+        rows.map(|row| JsonRecord(keys.iter().cloned().zip(row).collect()))
+            .collect()
+    }
+}
 
-        serde_json::to_string_pretty(&str_table).unwrap() // only strings, should be ok
+/// Converts a json value into a cell.
+///
+/// Numbers are kept as numerical cells, strings are parsed like any other cell and `null` is
+/// a blank cell.
+/// Booleans become string cells, since tables have no boolean cells.
+fn value_to_cell(value: Value) -> Cell {
+    match value {
+        Value::Null => Cell::Blank,
+        Value::Number(n) => match n.as_i64().and_then(|n| i32::try_from(n).ok()) {
+            Some(int) => Cell::Int(int),
+            None => Cell::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(s) => Cell::from_str(&s).expect("infallible"),
+        Value::Bool(b) => Cell::Str(b.to_string()),
+        value => Cell::Str(value.to_string()),
+    }
+}
+
+/// Converts a cell into a json value.
+///
+/// Unless numbers are enabled in the format options, every cell is written as a string.
+/// Otherwise numerical cells are written as numbers and blank cells as `null`.
+/// If the formatted number is not a valid json number (e.g. due to a prefix), the unformatted
+/// value is used.
+fn cell_to_value(cell: &Cell, format_options: &FormatOptions, row: usize, column: usize) -> Value {
+    let formatted = cell.fmt(format_options, row, column);
+    if !format_options.json_numbers {
+        return Value::String(formatted);
+    }
+
+    match cell {
+        Cell::Int(v) => serde_json::from_str::<Number>(&formatted)
+            .map(Value::Number)
+            .unwrap_or_else(|_| Value::from(*v)),
+        Cell::Float(v) => serde_json::from_str::<Number>(&formatted)
+            .map(Value::Number)
+            .unwrap_or_else(|_| Number::from_f64(*v).map_or(Value::Null, Value::Number)),
+        Cell::Str(_) | Cell::Uncertain(..) => Value::String(formatted),
+        Cell::Blank => Value::Null,
+    }
+}
+
+/// A row of a json table, either an array of values or a record with its keys in order.
+pub(super) enum JsonRow {
+    Array(Vec<Value>),
+    Record(Vec<(String, Value)>),
+}
+
+impl<'de> Deserialize<'de> for JsonRow {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct JsonRowVisitor;
+
+        impl<'de> Visitor<'de> for JsonRowVisitor {
+            type Value = JsonRow;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "an array or an object")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonRow, A::Error> {
+                let mut values = Vec::new();
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(JsonRow::Array(values))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonRow, A::Error> {
+                let mut record = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    record.push(entry);
+                }
+                Ok(JsonRow::Record(record))
+            }
+        }
+
+        deserializer.deserialize_any(JsonRowVisitor)
+    }
+}
+
+/// A record that is serialized as an object keeping the order of its keys.
+pub(super) struct JsonRecord(Vec<(String, Value)>);
+
+impl Serialize for JsonRecord {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in self.0.iter() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
     }
 }
 
@@ -41,3 +214,38 @@ impl From<serde_json::Error> for ParseTableError {
         ParseTableError::Json(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::JsonLayout;
+    use crate::table::tests::cells;
+    use crate::table::{FormatOptions, Table};
+
+    #[test]
+    fn from_json_works() {
+        let table = Table::from_json(r#"[[1, 2.5, null], ["3", true]]"#, &None).unwrap();
+        assert_eq!(cells(&table)[0], ["1i", "2.5f", "[]"]);
+        assert_eq!(cells(&table)[1], ["3i", "true", "[]"]);
+
+        let table = Table::from_json(r#"[{"t": 1, "u": 2}, {"v": "x", "t": 3}]"#, &None).unwrap();
+        assert_eq!(cells(&table)[0], ["t", "u", "v"]);
+        assert_eq!(cells(&table)[1], ["1i", "2i", "[]"]);
+        assert_eq!(cells(&table)[2], ["3i", "[]", "x"]);
+    }
+
+    #[test]
+    fn to_json_records() {
+        let table = Table::from_json(r#"[["u", "t"], [1.5, null]]"#, &None).unwrap();
+        let format_options = FormatOptions {
+            json_numbers: true,
+            json_layout: JsonLayout::Records,
+            ..Default::default()
+        };
+        let json = table.to_json(&format_options).replace([' ', '\n'], "");
+        assert_eq!(json, r#"[{"u":1.5,"t":null}]"#);
+
+        let table = Table::from_json(r#"[["U", "U", "U_2", "U"], [1, 2, 3, 4]]"#, &None).unwrap();
+        let json = table.to_json(&format_options).replace([' ', '\n'], "");
+        assert_eq!(json, r#"[{"U":1,"U_2":2,"U_2_2":3,"U_3":4}]"#);
+    }
+}
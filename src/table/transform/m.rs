@@ -1,42 +1,422 @@
+use crate::cli::Notation;
 use crate::table::cell::Cell;
 use crate::table::transform::ParseTableError;
-use crate::table::Table;
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::table::{FormatOptions, Table};
+use crate::util;
 use std::collections::VecDeque;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::str::FromStr;
 
 impl Table {
-    pub fn from_m(raw: &str, _: &Option<String>) -> Result<Table, ParseTableError> {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"\[([^\[\]]+)\]").expect("should be valid regex");
+    /// Construct a table from a matrix or cell array literal in a Matlab file.
+    ///
+    /// The additional data may name the variable to read (e.g. 'data.m:voltage'), otherwise the
+    /// first literal in the file is used.
+    /// Elements may be separated by commas or whitespace and rows by semicolons or line breaks.
+    /// Comments and line continuations ('...') are skipped.
+    pub fn from_m(raw: &str, additional_data: &Option<String>) -> Result<Table, ParseTableError> {
+        let mut variable = None;
+        for (key, value) in util::additional_options(additional_data) {
+            match key {
+                "" | "var" => variable = Some(value.trim()),
+                _ => return Err(ParseTableError::invalid_option(key, value)),
+            }
+        }
+
+        let mut lexer = Lexer::new(raw);
+        while let Some((name, table)) = lexer.next_literal()? {
+            match (variable, name) {
+                (None, _) => return Ok(table),
+                (Some(variable), Some(name)) if variable == name => return Ok(table),
+                _ => continue,
+            }
+        }
+
+        Err(match variable {
+            None => ParseMTableError::NoLiteral,
+            Some(variable) => ParseMTableError::UnknownVariable {
+                name: variable.to_string(),
+            },
+        }
+        .into())
+    }
+
+    /// Construct a Matlab representation assigning the table to a variable.
+    ///
+    /// Tables containing only numerical and blank cells are written as a matrix, tables
+    /// containing strings or values with uncertainty are written as a cell array, the latter as
+    /// strings.
+    /// Blank cells are written as `NaN`.
+    /// Numbers only honour the precision, significant digits and notation, so that they stay
+    /// valid Matlab literals. SI notation is written as engineering notation without the prefix.
+    pub fn to_m(&self, format_options: &FormatOptions) -> String {
+        let number_options = FormatOptions {
+            precision: format_options.precision.clone(),
+            significant: format_options.significant.clone(),
+            notation: format_options
+                .notation
+                .iter()
+                .map(|(selector, notation)| match notation {
+                    Notation::Si => (selector.clone(), Notation::Engineering),
+                    notation => (selector.clone(), *notation),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let is_cell_array = self
+            .values
+            .iter()
+            .flatten()
+            .any(|c| matches!(c, Cell::Str(_) | Cell::Uncertain(..)));
+        let (open, close) = match is_cell_array {
+            false => ('[', ']'),
+            true => ('{', '}'),
+        };
+
+        let rows: Vec<String> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(r, row)| {
+                let items = row.iter().enumerate().map(|(i, cell)| match cell {
+                    Cell::Int(_) | Cell::Float(_) => cell.fmt(&number_options, r, i),
+                    Cell::Str(s) => format!("'{}'", s.replace('\'', "''")),
+                    Cell::Uncertain(..) => {
+                        format!("'{}'", cell.fmt(format_options, r, i).replace('\'', "''"))
+                    }
+                    Cell::Blank => String::from("NaN"),
+                });
+                String::from("    ") + &itertools::join(items, " ")
+            })
+            .collect();
+
+        format!(
+            "{} = {open} ...\n{} ...\n{close};\n",
+            format_options.m_var,
+            itertools::join(rows, "; ...\n")
+        )
+    }
+}
+
+/// A simple lexer for Matlab files that only understands matrix and cell array literals.
+///
+/// Everything outside of literals, except for comments and variable assignments, is skipped.
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lexer {
+    fn new(raw: &str) -> Self {
+        Lexer {
+            chars: raw.chars().collect(),
+            pos: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.get(self.pos + n).copied()
+    }
+
+    fn starts_with(&self, pattern: &str) -> bool {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_nth(i) == Some(c))
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        match c {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            _ => self.column += 1,
+        }
+        Some(c)
+    }
+
+    fn unexpected(&self, found: impl ToString) -> ParseMTableError {
+        ParseMTableError::Unexpected {
+            found: found.to_string(),
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    /// Skips everything until the end of the line, the line break itself is not skipped.
+    fn skip_line(&mut self) {
+        while !matches!(self.peek(), None | Some('\n')) {
+            self.bump();
+        }
+    }
+
+    /// Skips a comment, block comments ('%{' to '%}') are skipped entirely.
+    fn skip_comment(&mut self) {
+        let line_start = self.pos == 0 || self.chars[self.pos - 1] == '\n';
+        if !(line_start && self.starts_with("%{")) {
+            return self.skip_line();
+        }
+
+        while self.peek().is_some() {
+            self.skip_line();
+            self.bump();
+            let rest: String = self.chars[self.pos..]
+                .iter()
+                .take_while(|c| **c != '\n')
+                .collect();
+            if rest.trim() == "%}" {
+                return self.skip_line();
+            }
+        }
+    }
+
+    /// Skips whitespace, comments and line continuations outside of literals.
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('%') => self.skip_comment(),
+                Some('.') if self.starts_with("...") => {
+                    self.skip_line();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Checks if the quote at the current position is a transpose operator instead of the start
+    /// of a string, which is the case if it directly follows a value.
+    fn is_transpose(&self) -> bool {
+        let previous = self.pos.checked_sub(1).map(|pos| self.chars[pos]);
+        previous
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | ']' | '}' | ')' | '\''))
+    }
+
+    /// Finds the next literal in the file and returns it with the name of its variable, if it is
+    /// directly assigned to one.
+    fn next_literal(&mut self) -> Result<Option<(Option<String>, Table)>, ParseMTableError> {
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                None => return Ok(None),
+                Some('[' | '{') => return Ok(Some((None, self.literal()?))),
+                Some('\'') if self.is_transpose() => {
+                    self.bump();
+                }
+                Some('\'' | '"') => {
+                    self.string()?;
+                }
+                Some(c) if c.is_alphabetic() => {
+                    let mut name = String::new();
+                    while let Some(c) = self.peek().filter(|c| c.is_alphanumeric() || *c == '_') {
+                        name.push(c);
+                        self.bump();
+                    }
+
+                    self.skip_trivia();
+                    if self.peek() != Some('=') || self.peek_nth(1) == Some('=') {
+                        continue;
+                    }
+                    self.bump();
+
+                    self.skip_trivia();
+                    if let Some('[' | '{') = self.peek() {
+                        return Ok(Some((Some(name), self.literal()?)));
+                    }
+                }
+                Some(_) => {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    /// Parses a matrix or cell array literal starting at the opening bracket.
+    fn literal(&mut self) -> Result<Table, ParseMTableError> {
+        let (line, column) = (self.line, self.column);
+        let close = match self.bump() {
+            Some('[') => ']',
+            Some('{') => '}',
+            _ => unreachable!("literals start with an opening bracket"),
+        };
+
+        let mut table = VecDeque::new();
+        let mut row = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(ParseMTableError::Unclosed { line, column }),
+                Some(c) if c == close => {
+                    self.bump();
+                    if !row.is_empty() {
+                        table.push_back(row);
+                    }
+                    return Ok(Table::from(table));
+                }
+                Some(';' | '\n') => {
+                    self.bump();
+                    if !row.is_empty() {
+                        table.push_back(std::mem::take(&mut row));
+                    }
+                }
+                Some(c) if c.is_whitespace() || c == ',' => {
+                    self.bump();
+                }
+                Some('%') => self.skip_line(),
+                Some('.') if self.starts_with("...") => {
+                    self.skip_line();
+                    self.bump();
+                }
+                Some('\'' | '"') => row.push(match self.string()? {
+                    s if s.is_empty() => Cell::Blank,
+                    s => Cell::Str(s),
+                }),
+                Some(c) if c.is_alphanumeric() || matches!(c, '.' | '+' | '-') => {
+                    row.push(self.number()?)
+                }
+                Some(c) => return Err(self.unexpected(c)),
+            }
         }
+    }
+
+    /// Parses a number followed by additions or subtractions.
+    ///
+    /// An operator directly after a number is binary, so `[1-2 3]` is `[-1 3]` as in Matlab,
+    /// while a whitespace in front of it makes it the sign of the next element, as in `[1 -2]`.
+    fn number(&mut self) -> Result<Cell, ParseMTableError> {
+        let mut cell = self.operand()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.bump();
+            while matches!(self.peek(), Some(' ' | '\t')) {
+                self.bump();
+            }
+            let operand = self.operand()?;
 
-        let relevant_part = RE.find(raw).ok_or(ParseMTableError)?.as_str();
-        let values = &relevant_part[1..(relevant_part.len() - 1)].replace("...", "");
+            let int = match (&cell, &operand) {
+                (Cell::Int(a), Cell::Int(b)) if op == '+' => a.checked_add(*b),
+                (Cell::Int(a), Cell::Int(b)) => a.checked_sub(*b),
+                _ => None,
+            };
+            cell = match int {
+                Some(int) => Cell::Int(int),
+                None => {
+                    let a = cell.as_f64().expect("operands are numbers");
+                    let b = operand.as_f64().expect("operands are numbers");
+                    Cell::Float(if op == '+' { a + b } else { a - b })
+                }
+            };
+        }
+        Ok(cell)
+    }
 
-        let mut raw_table = VecDeque::new();
-        let rows = values.split(';').map(str::trim);
-        for row in rows {
-            let mut row_items = Vec::new();
-            for cell in row.split(' ') {
-                row_items.push(Cell::from_str(cell).unwrap());
+    /// Parses a single number, including 'NaN' and 'Inf', that may start with a sign.
+    fn operand(&mut self) -> Result<Cell, ParseMTableError> {
+        let (line, column) = (self.line, self.column);
+        let mut token = String::new();
+        if let Some(sign @ ('+' | '-')) = self.peek() {
+            token.push(sign);
+            self.bump();
+        }
+        while let Some(c) = self.peek() {
+            let exponent_sign =
+                matches!(c, '+' | '-') && matches!(token.chars().last(), Some('e' | 'E'));
+            if !(c.is_alphanumeric() || c == '.' || exponent_sign) {
+                break;
             }
-            raw_table.push_back(row_items);
+            token.push(c);
+            self.bump();
         }
 
-        Ok(Table::from(raw_table))
+        let unsigned = token.trim_start_matches(['+', '-']);
+        let negative = token.starts_with('-');
+        let cell = match unsigned.to_lowercase().as_str() {
+            "nan" => Cell::Float(f64::NAN),
+            "inf" if negative => Cell::Float(f64::NEG_INFINITY),
+            "inf" => Cell::Float(f64::INFINITY),
+            _ => match token.parse::<i32>() {
+                Ok(int) => Cell::Int(int),
+                Err(_) => match token.parse::<f64>() {
+                    Ok(float) => Cell::Float(float),
+                    Err(_) => {
+                        return Err(ParseMTableError::Unexpected {
+                            found: token,
+                            line,
+                            column,
+                        })
+                    }
+                },
+            },
+        };
+        Ok(cell)
+    }
+
+    /// Parses a string quoted by `'` or `"`, doubled quotes are unescaped.
+    fn string(&mut self) -> Result<String, ParseMTableError> {
+        let (line, column) = (self.line, self.column);
+        let quote = self.bump().expect("strings start with a quote");
+        let mut string = String::new();
+        loop {
+            match self.bump() {
+                None | Some('\n') => return Err(ParseMTableError::Unclosed { line, column }),
+                Some(c) if c == quote && self.peek() == Some(quote) => {
+                    self.bump();
+                    string.push(quote);
+                }
+                Some(c) if c == quote => return Ok(string),
+                Some(c) => string.push(c),
+            }
+        }
     }
 }
 
 #[derive(Debug)]
-pub struct ParseMTableError;
+pub enum ParseMTableError {
+    NoLiteral,
+    UnknownVariable {
+        name: String,
+    },
+    Unexpected {
+        found: String,
+        line: usize,
+        column: usize,
+    },
+    Unclosed {
+        line: usize,
+        column: usize,
+    },
+}
 
 impl Display for ParseMTableError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "could not find relevant part")
+        match self {
+            ParseMTableError::NoLiteral => write!(f, "could not find a matrix or cell array"),
+            ParseMTableError::UnknownVariable { name } => {
+                write!(f, "could not find a matrix or cell array named '{name}'")
+            }
+            ParseMTableError::Unexpected {
+                found,
+                line,
+                column,
+            } => write!(f, "unexpected '{found}' at line {line}, column {column}"),
+            ParseMTableError::Unclosed { line, column } => {
+                write!(
+                    f,
+                    "unclosed literal starting at line {line}, column {column}"
+                )
+            }
+        }
     }
 }
 
@@ -50,13 +430,17 @@ impl From<ParseMTableError> for ParseTableError {
 
 #[cfg(test)]
 mod test {
+    use crate::cli::{DecimalSeparator, Notation};
+    use crate::table::tests::cells;
+    use crate::util::Selector;
     use crate::*;
+    use std::num::NonZeroU16;
 
     const EXAMPLE_RAW: &str = include_str!("../../../examples/example.m");
-    const EXAMPLE_SIZE: (usize, usize) = (3, 38 - 1);
+    const EXAMPLE_SIZE: (usize, usize) = (3, 38);
 
     const EXAMPLE2_RAW: &str = include_str!("../../../examples/example2.m");
-    const EXAMPLE2_SIZE: (usize, usize) = (1, 178 - 1);
+    const EXAMPLE2_SIZE: (usize, usize) = (1, 178);
 
     #[test]
     fn table_size() {
@@ -68,4 +452,89 @@ mod test {
         assert_eq!(example2_table.width, EXAMPLE2_SIZE.0);
         assert_eq!(example2_table.height, EXAMPLE2_SIZE.1);
     }
+
+    #[test]
+    fn variables() {
+        let raw = "% comment [1 2]\n\
+            x = 5;\n\
+            a = [1, 2,3 ; 4\t5   6 % trailing comment\n\
+                 7 -8 ...continued\n\
+                 9];\n\
+            b = {1e-3 NaN, -Inf 'it''s'};\n";
+
+        let a = Table::from_m(raw, &None).unwrap();
+        assert_eq!((a.width, a.height), (3, 3));
+        assert_eq!(cells(&a)[2], ["7i", "-8i", "9i"]);
+
+        let b = Table::from_m(raw, &Some(String::from("b"))).unwrap();
+        assert_eq!(cells(&b)[0], ["0.001f", "NaNf", "-inff", "it's"]);
+
+        let missing = Table::from_m(raw, &Some(String::from("c")));
+        assert!(missing.is_err());
+
+        let transposed = "x = [1 2 3]';\ny = (x')' + a';\nz = [4 5];";
+        let z = Table::from_m(transposed, &Some(String::from("z"))).unwrap();
+        assert_eq!(cells(&z)[0], ["4i", "5i"]);
+
+        let sums = Table::from_m("a = [1-2 3, 1 -2, 1+ 2.5, -1--2-3, 2e-1+1]", &None).unwrap();
+        assert_eq!(
+            cells(&sums)[0],
+            ["-1i", "3i", "1i", "-2i", "3.5f", "-2i", "1.2f"]
+        );
+        let error = Table::from_m("a = [1- x]", &None).unwrap_err();
+        assert_eq!(error.to_string(), "unexpected 'x' at line 1, column 9");
+
+        let error = Table::from_m("a = [1 2\n3 x4]", &None).unwrap_err();
+        assert_eq!(error.to_string(), "unexpected 'x4' at line 2, column 3");
+    }
+
+    #[test]
+    fn to_m_works() {
+        let format_options = FormatOptions {
+            m_var: String::from("x"),
+            ..Default::default()
+        };
+
+        let table = Table::from_m("[1 2.5; 3 NaN]", &None).unwrap();
+        assert_eq!(
+            table.to_m(&format_options),
+            "x = [ ...\n    1 2.5; ...\n    3 NaN ...\n];\n"
+        );
+
+        let table = Table::from_dat("it's\t1\n2", &None, false).unwrap();
+        assert_eq!(
+            table.to_m(&format_options),
+            "x = { ...\n    'it''s' 1; ...\n    2 NaN ...\n};\n"
+        );
+    }
+
+    #[test]
+    fn to_m_numbers_stay_literals() {
+        let mut format_options = FormatOptions {
+            precision: vec![(Selector::ALL, 1)],
+            notation: vec![
+                (Selector::ALL, Notation::Si),
+                ("1".parse().unwrap(), Notation::Scientific),
+            ],
+            decimal_sep: vec![(Selector::ALL, DecimalSeparator::Comma)],
+            sign: vec![(Selector::ALL, true)],
+            prefix: vec![(Selector::ALL, String::from("~"))],
+            suffix: vec![(Selector::ALL, String::from(" V"))],
+            m_var: String::from("x"),
+            ..Default::default()
+        };
+
+        let table = Table::from_m("[1.5 1200 12345]", &None).unwrap();
+        assert_eq!(
+            table.to_m(&format_options),
+            "x = [ ...\n    1.5e+00 1.2e+03 12.3e+03 ...\n];\n"
+        );
+
+        format_options.notation = vec![(Selector::ALL, Notation::Engineering)];
+        format_options.significant = vec![(Selector::ALL, NonZeroU16::new(2).unwrap())];
+        assert_eq!(
+            table.to_m(&format_options),
+            "x = [ ...\n    1.5e+00 1.2e+03 12e+03 ...\n];\n"
+        );
+    }
 }
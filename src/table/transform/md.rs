@@ -1,13 +1,45 @@
+use crate::table::cell::Cell;
+use crate::table::transform::ParseTableError;
 use crate::table::{FormatOptions, Table};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 impl Table {
+    /// Construct a table from the first GitHub-style pipe table found in a markdown file.
+    ///
+    /// The alignment row below the header row is skipped, escaped pipes ('\|') are kept as part
+    /// of the cell.
+    /// The table ends at the first line that is not a table row anymore.
+    pub fn from_md(raw: &str, _: &Option<String>) -> Result<Table, ParseTableError> {
+        let lines: Vec<&str> = raw.lines().collect();
+        let start = lines
+            .windows(2)
+            .position(|w| is_table_row(w[0]) && is_alignment_row(w[1]))
+            .ok_or(ParseMdTableError)?;
+
+        let mut table: VecDeque<Vec<Cell>> = VecDeque::new();
+        table.push_back(split_row(lines[start]));
+        for line in lines.iter().skip(start + 2) {
+            if !is_table_row(line) {
+                break;
+            }
+            table.push_back(split_row(line));
+        }
+
+        Ok(table.into())
+    }
+
     pub fn to_md(&self, format_options: &FormatOptions) -> String {
         let mut str_components: Vec<String> = Vec::new();
 
         // first row
         str_components.push("| ".to_string());
         for (i, cell) in self.values[0].iter().enumerate() {
-            str_components.push(cell.fmt(format_options, i));
+            str_components.push(cell.fmt(format_options, 0, i));
             str_components.push(String::from(" | "));
         }
         str_components.push(String::from("\n"));
@@ -20,10 +52,10 @@ impl Table {
         str_components.push(String::from("\n"));
 
         // rest
-        for row in self.values.iter().skip(1) {
+        for (r, row) in self.values.iter().enumerate().skip(1) {
             str_components.push(String::from("| "));
             for (i, cell) in row.iter().enumerate() {
-                str_components.push(cell.fmt(format_options, i));
+                str_components.push(cell.fmt(format_options, r, i));
                 str_components.push(String::from(" | "));
             }
             str_components.push(String::from("\n"));
@@ -32,3 +64,95 @@ impl Table {
         str_components.into_iter().collect()
     }
 }
+
+/// Checks if a line may be a row of a pipe table, that is, it contains an unescaped pipe.
+pub(super) fn is_table_row(line: &str) -> bool {
+    split_cells(line).len() > 1 || line.trim().starts_with('|')
+}
+
+/// Checks if a line is the alignment row of a pipe table (e.g. '| --- | :-: | --: |').
+pub(super) fn is_alignment_row(line: &str) -> bool {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"^\s*:?-+:?\s*$").expect("should be valid regex");
+    }
+
+    is_table_row(line) && split_cells(line).iter().all(|c| RE.is_match(c))
+}
+
+/// Splits a table row into its cells.
+fn split_row(line: &str) -> Vec<Cell> {
+    split_cells(line)
+        .iter()
+        .map(|c| Cell::from_str(c.trim()).expect("infallible"))
+        .collect()
+}
+
+/// Splits a table row at its unescaped pipes, leading and trailing pipes are optional.
+fn split_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+
+    let mut cells = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some('|')) => {
+                cells.last_mut().expect("never empty").push('|');
+                chars.next();
+            }
+            ('|', _) => cells.push(String::new()),
+            (c, _) => cells.last_mut().expect("never empty").push(c),
+        }
+    }
+
+    // a trailing pipe closes the last cell instead of opening a new one
+    if line.ends_with('|') && !line.ends_with("\\|") {
+        cells.pop();
+    }
+    cells
+}
+
+#[derive(Debug)]
+pub struct ParseMdTableError;
+
+impl Display for ParseMdTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "could not find a table with an alignment row")
+    }
+}
+
+impl Error for ParseMdTableError {}
+
+impl From<ParseMdTableError> for ParseTableError {
+    fn from(value: ParseMdTableError) -> Self {
+        ParseTableError::Md(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::tests::cells;
+    use crate::table::Table;
+
+    #[test]
+    fn from_md_works() {
+        let raw = "Some text\n\n\
+            | Name | Value |\n\
+            | :--- | ----: |\n\
+            | a \\| b | 1,5 |\n\
+            |   |  |\n\
+            x | 2\n\
+            \n\
+            | not | part |";
+        let table = Table::from_md(raw, &None).unwrap();
+        assert_eq!(
+            cells(&table),
+            [
+                ["Name", "Value"],
+                ["a | b", "1.5f"],
+                ["[]", "[]"],
+                ["x", "2i"]
+            ]
+        );
+    }
+}
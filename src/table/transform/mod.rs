@@ -1,14 +1,19 @@
 use crate::table::transform::csv::ParseCsvTableError;
 use crate::table::transform::json::ParseJsonTableError;
 use crate::table::transform::m::ParseMTableError;
+use crate::table::transform::md::ParseMdTableError;
+use crate::table::transform::ndjson::ParseNdjsonTableError;
+use crate::table::transform::tex::ParseTexTableError;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
 pub mod csv;
 pub mod dat;
+pub mod detect;
 pub mod json;
 pub mod m;
 pub mod md;
+pub mod ndjson;
 pub mod tex;
 
 /// Error if parsing tables fail.
@@ -17,6 +22,20 @@ pub enum ParseTableError {
     Csv(ParseCsvTableError),
     Json(ParseJsonTableError),
     M(ParseMTableError),
+    Md(ParseMdTableError),
+    Tex(ParseTexTableError),
+    Ndjson(ParseNdjsonTableError),
+    InvalidOption { key: String, value: String },
+}
+
+impl ParseTableError {
+    /// Creates an error for an option of the additional data that could not be understood.
+    pub fn invalid_option(key: &str, value: &str) -> Self {
+        ParseTableError::InvalidOption {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
 }
 
 impl Display for ParseTableError {
@@ -25,6 +44,15 @@ impl Display for ParseTableError {
             ParseTableError::Csv(c) => c.fmt(f),
             ParseTableError::Json(j) => j.fmt(f),
             ParseTableError::M(m) => m.fmt(f),
+            ParseTableError::Md(m) => m.fmt(f),
+            ParseTableError::Tex(t) => t.fmt(f),
+            ParseTableError::Ndjson(n) => n.fmt(f),
+            ParseTableError::InvalidOption { key, value } if key.is_empty() => {
+                write!(f, "invalid option '{value}'")
+            }
+            ParseTableError::InvalidOption { key, value } => {
+                write!(f, "invalid value '{value}' for option '{key}'")
+            }
         }
     }
 }
@@ -0,0 +1,109 @@
+use crate::table::transform::json::JsonRow;
+use crate::table::transform::ParseTableError;
+use crate::table::{FormatOptions, Table};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+impl Table {
+    /// Construct a table from the contents of a json lines file.
+    ///
+    /// Every non-empty line holds either an array or an object.
+    /// For objects, the keys of all lines are collected in order of appearance and written as
+    /// header row.
+    pub fn from_ndjson(raw: &str, _: &Option<String>) -> Result<Table, ParseTableError> {
+        let mut parsed_rows = Vec::new();
+        for (i, line) in raw.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let row: JsonRow = serde_json::from_str(line)
+                .map_err(|error| ParseNdjsonTableError { line: i + 1, error })?;
+            parsed_rows.push(row);
+        }
+
+        Ok(Table::from_json_rows(parsed_rows))
+    }
+
+    /// Construct a json lines representation.
+    ///
+    /// The first row is used as keys, every other row is written as an object on its own line.
+    pub fn to_ndjson(&self, format_options: &FormatOptions) -> String {
+        let mut output = String::new();
+        for record in self.to_json_records(format_options) {
+            // only strings and numbers, should be ok
+            output += &(serde_json::to_string(&record).unwrap() + "\n");
+        }
+        output
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseNdjsonTableError {
+    line: usize,
+    error: serde_json::Error,
+}
+
+impl Display for ParseNdjsonTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // the position reported by serde_json is relative to the line, so it is replaced
+        let error = self.error.to_string();
+        let error = error
+            .rsplit_once(" at line ")
+            .map_or(error.as_str(), |(e, _)| e);
+        write!(
+            f,
+            "{error} at line {} column {}",
+            self.line,
+            self.error.column()
+        )
+    }
+}
+
+impl Error for ParseNdjsonTableError {}
+
+impl From<ParseNdjsonTableError> for ParseTableError {
+    fn from(value: ParseNdjsonTableError) -> Self {
+        ParseTableError::Ndjson(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::tests::cells;
+    use crate::table::{FormatOptions, Table};
+
+    #[test]
+    fn from_ndjson_unions_keys() {
+        let raw = "{\"t\": 1, \"u\": 2.5}\n\n   \n{\"i\": \"x\", \"t\": 3}\n";
+        let table = Table::from_ndjson(raw, &None).unwrap();
+        assert_eq!(
+            cells(&table),
+            [["t", "u", "i"], ["1i", "2.5f", "[]"], ["3i", "[]", "x"]]
+        );
+    }
+
+    #[test]
+    fn from_ndjson_reports_line() {
+        let raw = "[1, 2]\n\n[3, 4\n";
+        let error = Table::from_ndjson(raw, &None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "EOF while parsing a list at line 3 column 5"
+        );
+    }
+
+    #[test]
+    fn to_ndjson_round_trip() {
+        let table = Table::from_dat("t\tname\n1\ta b\n2.5\t", &None, false).unwrap();
+        let raw = table.to_ndjson(&FormatOptions::default());
+        assert_eq!(
+            raw,
+            "{\"t\":\"1\",\"name\":\"a b\"}\n{\"t\":\"2.5\",\"name\":\"\"}\n"
+        );
+        assert_eq!(
+            cells(&Table::from_ndjson(&raw, &None).unwrap()),
+            cells(&table)
+        );
+    }
+}
@@ -1,13 +1,95 @@
+use crate::table::cell::Cell;
+use crate::table::transform::ParseTableError;
 use crate::table::FormatOptions;
 use crate::table::Table;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 impl Table {
+    /// Construct a table from the first `tabular` or `longtable` environment of a tex file.
+    ///
+    /// Rows are split at '\\' and cells at '&'.
+    /// Rules like '\hline' or the booktabs rules are ignored and the siunitx wrappers '\num{}',
+    /// '\SI{}{}' and '\qty{}{}', optionally with options in brackets, are stripped so that their
+    /// numbers are parsed as numerical cells.
+    /// A group enclosing the whole cell, as used for headers of siunitx columns, is stripped too.
+    pub fn from_tex(raw: &str, _: &Option<String>) -> Result<Table, ParseTableError> {
+        lazy_static! {
+            static ref BEGIN: Regex =
+                Regex::new(r"\\begin\{(?<env>tabular\*?|tabularx|longtable)\}")
+                    .expect("should be valid regex");
+            static ref COMMENT: Regex =
+                Regex::new(r"(?m)(?<keep>^|[^\\])%.*$").expect("should be valid regex");
+            static ref RULE: Regex = Regex::new(
+                r"\\(?:hline|toprule|midrule|bottomrule|endfirsthead|endhead|endfoot|endlastfoot)\b|\\addlinespace(?:\[[^\]]*\])?|\\(?:cmidrule|cline)(?:\([^)]*\))?\{[^}]*\}"
+            )
+            .expect("should be valid regex");
+            static ref ROW_SEP: Regex =
+                Regex::new(r"\\\\(?:\[[^\]]*\])?").expect("should be valid regex");
+            static ref NUM: Regex = Regex::new(
+                r"\\num(?:\[[^\]]*\])?\{(?<v>[^{}]*)\}|\\(?:SI|qty)(?:\[[^\]]*\])?\{(?<q>[^{}]*)\}\{[^{}]*\}"
+            )
+            .expect("should be valid regex");
+        }
+
+        let raw = COMMENT.replace_all(raw, "$keep");
+        let begin = BEGIN.captures(&raw).ok_or(ParseTexTableError::MissingBegin)?;
+        let env = begin.name("env").expect("always captured").as_str();
+        let mut body = &raw[begin.get(0).expect("always exists").end()..];
+
+        // skip position, width and column specification
+        let groups = match env {
+            "tabular*" | "tabularx" => 2,
+            _ => 1,
+        };
+        body = skip_group(body, '[', ']');
+        for _ in 0..groups {
+            body = skip_group(body, '{', '}');
+        }
+
+        let end = format!("\\end{{{env}}}");
+        let end = body
+            .find(&end)
+            .ok_or_else(|| ParseTexTableError::MissingEnd {
+                environment: env.to_string(),
+            })?;
+        let body = RULE.replace_all(&body[..end], "");
+
+        let mut table: VecDeque<Vec<Cell>> = VecDeque::new();
+        for row in ROW_SEP.split(&body) {
+            if row.trim().is_empty() {
+                continue;
+            }
+
+            let cells = split_cells(row).into_iter().map(|c| {
+                let c = NUM.replace_all(&c, "$v$q");
+                let c = c.trim();
+                let c = match c.starts_with('{') && skip_group(c, '{', '}').is_empty() {
+                    true => &c[1..c.len() - 1],
+                    false => c,
+                };
+                let c = c
+                    .strip_prefix('$')
+                    .and_then(|c| c.strip_suffix('$'))
+                    .unwrap_or(c);
+                Cell::from_str(c.trim()).expect("infallible")
+            });
+            table.push_back(cells.collect());
+        }
+
+        Ok(table.into())
+    }
+
     pub fn to_tex(&self, format_options: &FormatOptions) -> String {
         let mut rows = Vec::with_capacity(self.height);
-        for row in self.values.iter() {
+        for (r, row) in self.values.iter().enumerate() {
             let mut items = Vec::with_capacity(self.width);
             for (i, cell) in row.iter().enumerate() {
-                items.push(cell.fmt(format_options, i));
+                items.push(cell.fmt(format_options, r, i));
             }
             rows.push(itertools::join(items, " & "));
         }
@@ -18,3 +100,105 @@ impl Table {
         }
     }
 }
+
+/// Skips a leading group enclosed by `open` and `close`, respecting nested groups.
+///
+/// If the input does not start with the group (ignoring whitespace), it is returned unchanged.
+fn skip_group(input: &str, open: char, close: char) -> &str {
+    let trimmed = input.trim_start();
+    if !trimmed.starts_with(open) {
+        return input;
+    }
+
+    let mut depth = 0;
+    for (i, c) in trimmed.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return &trimmed[i + c.len_utf8()..];
+            }
+        }
+    }
+    ""
+}
+
+/// Splits a row at its unescaped '&', escaped ones ('\&') are kept as '&'.
+fn split_cells(row: &str) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some('&')) => {
+                cells.last_mut().expect("never empty").push('&');
+                chars.next();
+            }
+            ('&', _) => cells.push(String::new()),
+            (c, _) => cells.last_mut().expect("never empty").push(c),
+        }
+    }
+    cells
+}
+
+#[derive(Debug)]
+pub enum ParseTexTableError {
+    MissingBegin,
+    MissingEnd { environment: String },
+}
+
+impl Display for ParseTexTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseTexTableError::MissingBegin => {
+                write!(f, "could not find a tabular or longtable environment")
+            }
+            ParseTexTableError::MissingEnd { environment } => {
+                write!(f, "could not find the end of the {environment} environment")
+            }
+        }
+    }
+}
+
+impl Error for ParseTexTableError {}
+
+impl From<ParseTexTableError> for ParseTableError {
+    fn from(value: ParseTexTableError) -> Self {
+        ParseTableError::Tex(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::tests::cells;
+    use crate::table::Table;
+
+    #[test]
+    fn from_tex_works() {
+        let raw = r"\begin{table}
+            \begin{tabular}{l|S[table-format=1.2]}
+              \toprule
+              Name & {Value} \\ \midrule
+              a \& b & \num{1.25} \\
+              c & \SI{3}{\volt} \\[2pt] \hline
+              % d & 4 \\
+              & $-2.5$ \\
+              {e}{f} & \num[round-precision=2]{1.5} \\
+              g & \SI[per-mode=symbol]{3}{\volt\per\second} \\
+              \bottomrule
+            \end{tabular}
+          \end{table}";
+        let table = Table::from_tex(raw, &None).unwrap();
+        assert_eq!(
+            cells(&table),
+            [
+                ["Name", "Value"],
+                ["a & b", "1.25f"],
+                ["c", "3i"],
+                ["[]", "-2.5f"],
+                ["{e}{f}", "1.5f"],
+                ["g", "3i"]
+            ]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::table::cell::Cell;
+use crate::table::Table;
+
+impl Table {
+    /// Combines value columns with their uncertainty columns.
+    ///
+    /// Every numerical value with a non-zero numerical uncertainty becomes an uncertain cell, all
+    /// other cells, like the header, are kept as they are.
+    /// The uncertainty columns are removed afterwards, so the columns of all pairs refer to the
+    /// table before combining.
+    pub fn pair_uncertainties(&mut self, pairs: &[(usize, usize)]) {
+        for row in self.values.iter_mut() {
+            for (value, uncertainty) in pairs {
+                let v = row.get(*value).and_then(Cell::as_f64);
+                let u = row.get(*uncertainty).and_then(Cell::as_f64);
+                if let (Some(v), Some(u)) = (v, u) {
+                    if v.is_finite() && u.is_finite() && u != 0.0 {
+                        row[*value] = Cell::Uncertain(v, u);
+                    }
+                }
+            }
+        }
+
+        let mut removed: Vec<usize> = pairs
+            .iter()
+            .map(|(_, uncertainty)| *uncertainty)
+            .filter(|uncertainty| !pairs.iter().any(|(value, _)| value == uncertainty))
+            .collect();
+        removed.sort_unstable();
+        removed.dedup();
+        for row in self.values.iter_mut() {
+            for i in removed.iter().rev() {
+                if *i < row.len() {
+                    row.remove(*i);
+                }
+            }
+        }
+        self.width = self.values.iter().map(Vec::len).max().unwrap_or(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Notation, UncertaintyStyle};
+    use crate::table::{FormatOptions, Table};
+    use crate::util::Selector;
+
+    #[test]
+    fn pair_uncertainties() {
+        let raw =
+            "U\tdU\tI\tdI\n1.2345\t0.0432\t1234.5\t0.0196\n-0.5\t0.096\t1234.5\t234\n2\t\t3\t0";
+        let mut table = Table::from_dat(raw, &None, false).unwrap();
+        table.pair_uncertainties(&[(0, 1), (2, 3)]);
+        assert_eq!(table.width, 2);
+
+        let mut format_options = FormatOptions::default();
+        let rows = |table: &Table, format_options: &FormatOptions| {
+            table
+                .values
+                .iter()
+                .map(|r| itertools::join(r.iter().map(|c| c.fmt(format_options, 0, 0)), " | "))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            rows(&table, &format_options),
+            [
+                "U | I",
+                "1.23 ± 0.04 | 1234.500 ± 0.020",
+                "-0.5 ± 0.1 | 1200 ± 200",
+                "2 | 3"
+            ]
+        );
+
+        format_options.uncertainty_style = UncertaintyStyle::Paren;
+        format_options.uncertainty_digits = Some(2);
+        format_options.sign = vec![(Selector::ALL, true)];
+        assert_eq!(
+            rows(&table, &format_options)[1..3],
+            ["+1.235(43) | +1234.500(20)", "-0.500(96) | +1230(230)"]
+        );
+
+        format_options.uncertainty_style = UncertaintyStyle::Pm;
+        format_options.uncertainty_digits = None;
+        format_options.sign = vec![];
+        format_options.latex = true;
+        format_options.notation = vec![(Selector::ALL, Notation::Scientific)];
+        assert_eq!(
+            rows(&table, &format_options)[1..3],
+            [
+                "$(1.23 \\pm 0.04) \\times 10^{0}$ | $(1.234500 \\pm 0.000020) \\times 10^{3}$",
+                "$(-5 \\pm 1) \\times 10^{-1}$ | $(1.2 \\pm 0.2) \\times 10^{3}$"
+            ]
+        );
+
+        format_options.uncertainty_style = UncertaintyStyle::Siunitx;
+        format_options.notation = vec![];
+        assert_eq!(
+            rows(&table, &format_options)[2],
+            "\\num{-0.5 +- 0.1} | \\num{1200 +- 200}"
+        );
+    }
+}
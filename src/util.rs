@@ -49,6 +49,13 @@ where
     }
 }
 
+impl AnyRange<usize> {
+    /// Returns all indices within the range that are smaller than `len` in ascending order.
+    pub fn indices(&self, len: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..len).filter(move |i| self.contains(i))
+    }
+}
+
 /// An error type for parsing an `AnyRange` from a string.
 #[derive(Debug)]
 pub enum ParseAnyRangeError {
@@ -73,27 +80,25 @@ impl FromStr for AnyRange<usize> {
             static ref RE: Regex =
                 // once correctly parsed this should always parse correctly since the content of
                 // the regex is static
-                Regex::new(r"(?<start>\d+)?(?:(?<range>\.\.)(?<inclusive>=)?(?<end>\d+)?)?")
+                Regex::new(r"^(?<start>\d+)?(?:(?<range>\.\.)(?<inclusive>=)?(?<end>\d+)?)?$")
                     .expect("should be valid regex");
         }
 
         let captures = RE
             .captures(s)
             .ok_or_else(|| ParseAnyRangeError::InvalidFormat { raw: s.to_string() })?;
-        let start: Option<usize> = captures.name("start").map(|m| {
-            m.as_str()
-                .parse::<u16>()
-                .expect("regex must match 'start'")
-                .into()
-        });
+        let start: Option<usize> = captures
+            .name("start")
+            .map(|m| m.as_str().parse::<u16>().map(usize::from))
+            .transpose()
+            .map_err(|_| ParseAnyRangeError::InvalidFormat { raw: s.to_string() })?;
         let range = captures.name("range");
         let inclusive = captures.name("inclusive");
-        let end = captures.name("end").map(|m| {
-            m.as_str()
-                .parse::<u16>()
-                .expect("digits only should be parseable as u16")
-                .into()
-        });
+        let end = captures
+            .name("end")
+            .map(|m| m.as_str().parse::<u16>().map(usize::from))
+            .transpose()
+            .map_err(|_| ParseAnyRangeError::InvalidFormat { raw: s.to_string() })?;
 
         match (start, range, inclusive, end) {
             // 1
@@ -121,16 +126,112 @@ impl FromStr for AnyRange<usize> {
                 Ok(AnyRange::RangeToInclusive(RangeToInclusive { end }))
             }
 
-            _ => panic!("'{s}' correctly captured but could not be matched"),
+            // '' or '..='
+            _ => Err(ParseAnyRangeError::InvalidFormat { raw: s.to_string() }),
         }
     }
 }
 
+/// Selects the cells of a table a formatting rule applies to.
+///
+/// Selectors are written as 'r<range>' for rows, 'c<range>' for columns and 'r<range>c<range>'
+/// for the cells within both.
+/// A range without a letter selects columns.
+/// Rows are counted over all rows of a table, so row 0 is the header row if there is one.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Selector {
+    Rows(AnyRange<usize>),
+    Columns(AnyRange<usize>),
+    Cells(AnyRange<usize>, AnyRange<usize>),
+}
+
+impl Selector {
+    /// Selects every cell.
+    pub const ALL: Selector = Selector::Columns(AnyRange::RangeFull(RangeFull));
+
+    /// Checks if the cell at the given row and column is selected.
+    pub fn contains(&self, row: usize, column: usize) -> bool {
+        match self {
+            Selector::Rows(rows) => rows.contains(&row),
+            Selector::Columns(columns) => columns.contains(&column),
+            Selector::Cells(rows, columns) => rows.contains(&row) && columns.contains(&column),
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = ParseAnyRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('r') {
+            Some(rest) => match rest.split_once('c') {
+                Some((rows, columns)) => Ok(Selector::Cells(rows.parse()?, columns.parse()?)),
+                None => Ok(Selector::Rows(rest.parse()?)),
+            },
+            None => Ok(Selector::Columns(s.strip_prefix('c').unwrap_or(s).parse()?)),
+        }
+    }
+}
+
+/// Splits the additional data of an input file into its options.
+///
+/// Options are separated by ':' and have the form '<key>=<value>'.
+/// Options without a key are returned with an empty key, empty options are skipped.
+/// A leading empty option is a literal ':' instead, so 'file.csv::' reads a colon separated file.
+pub fn additional_options(additional_data: &Option<String>) -> Vec<(&str, &str)> {
+    let Some(additional_data) = additional_data else {
+        return Vec::new();
+    };
+
+    let (colon, additional_data) = match additional_data.strip_prefix(':') {
+        Some(rest) => (Some(("", ":")), rest),
+        None => (None, additional_data.as_str()),
+    };
+    let options = additional_data
+        .split(':')
+        .filter(|option| !option.is_empty())
+        .map(|option| option.split_once('=').unwrap_or(("", option)));
+    colon.into_iter().chain(options).collect()
+}
+
+/// Parses a single ascii character, either given directly or by its name.
+pub fn parse_char(value: &str) -> Option<u8> {
+    let c = match value {
+        "tab" => '\t',
+        "space" => ' ',
+        "colon" => ':',
+        "semicolon" => ';',
+        "comma" => ',',
+        "pipe" => '|',
+        "backslash" => '\\',
+        value => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return None,
+            }
+        }
+    };
+    u8::try_from(c).ok()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::util::AnyRange;
+    use crate::util::{additional_options, AnyRange, Selector};
     use std::ops::*;
 
+    #[test]
+    fn additional_options_works() {
+        let data = Some(String::from("|::quote='"));
+        assert_eq!(additional_options(&data), [("", "|"), ("quote", "'")]);
+        let data = Some(String::from(":"));
+        assert_eq!(additional_options(&data), [("", ":")]);
+        let data = Some(String::from("::quote='"));
+        assert_eq!(additional_options(&data), [("", ":"), ("quote", "'")]);
+        let data = Some(String::from("skip=1::"));
+        assert_eq!(additional_options(&data), [("skip", "1")]);
+    }
+
     #[test]
     fn from_str_works() {
         let start = 1;
@@ -159,6 +260,29 @@ mod tests {
         assert_eq!(
             "1..=2".parse::<AnyRange<usize>>().unwrap(),
             AnyRange::RangeInclusive(RangeInclusive::new(start, end))
-        )
+        );
+        assert!("".parse::<AnyRange<usize>>().is_err());
+        assert!("..=".parse::<AnyRange<usize>>().is_err());
+        assert!("1..2x".parse::<AnyRange<usize>>().is_err());
+        assert!("99999".parse::<AnyRange<usize>>().is_err());
+    }
+
+    #[test]
+    fn selector_works() {
+        let selector = |s: &str| s.parse::<Selector>().unwrap();
+        assert_eq!(selector("1..3"), Selector::Columns(AnyRange::Range(1..3)));
+        assert_eq!(selector("c1..3"), Selector::Columns(AnyRange::Range(1..3)));
+        assert_eq!(selector("r2"), Selector::Rows(AnyRange::Index(2)));
+        assert_eq!(
+            selector("r0c4.."),
+            Selector::Cells(AnyRange::Index(0), AnyRange::RangeFrom(4..))
+        );
+        assert!(selector("r0c4..").contains(0, 5));
+        assert!(!selector("r0c4..").contains(1, 5));
+        assert!(selector("r1..").contains(3, 0));
+        assert!(Selector::ALL.contains(3, 7));
+        assert!("r".parse::<Selector>().is_err());
+        assert!("c2r1".parse::<Selector>().is_err());
+        assert!("x1".parse::<Selector>().is_err());
     }
 }